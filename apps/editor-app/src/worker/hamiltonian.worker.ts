@@ -9,6 +9,7 @@ import {
   hasDifferentParity,
   initWasm,
   pathToRoadGrid,
+  type SearchOptionsType,
 } from "@hamiltonian/lib"
 
 // Message types
@@ -51,13 +52,13 @@ self.onmessage = async (event: MessageEvent<WorkerRequest>) => {
 
     switch (type) {
       case "findPath": {
-        const { start, end, gridSize, maxIterations } = payload as {
+        const { start, end, gridSize, options } = payload as {
           start: { row: number; col: number }
           end: { row: number; col: number }
           gridSize: { rows: number; cols: number }
-          maxIterations: number
+          options: number | SearchOptionsType
         }
-        result = findHamiltonianPath(start, end, gridSize, maxIterations)
+        result = findHamiltonianPath(start, end, gridSize, options)
         break
       }
 
@@ -0,0 +1,100 @@
+//! Millisecond clocks for wall-clock search budgets
+//!
+//! `std::time::Instant` is unavailable on `wasm32-unknown-unknown`, so the
+//! default clock reads `performance.now()` there and `Instant` natively.
+//! Anything implementing `Clock` can be passed in instead, e.g. a fake clock
+//! in tests.
+
+/// Monotonic time source in milliseconds
+pub trait Clock {
+    /// Milliseconds since an arbitrary fixed origin
+    fn now_ms(&mut self) -> f64;
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance, js_name = now)]
+        fn performance_now() -> f64;
+    }
+
+    /// `performance.now()`, available in browsers, workers and Node
+    #[derive(Debug, Default)]
+    pub struct SystemClock;
+
+    impl SystemClock {
+        /// Same constructor as the native clock, so callers need no `cfg`
+        pub fn new() -> Self {
+            SystemClock
+        }
+    }
+
+    impl super::Clock for SystemClock {
+        fn now_ms(&mut self) -> f64 {
+            performance_now()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::time::Instant;
+
+    /// `Instant` based clock with its origin at construction
+    #[derive(Debug)]
+    pub struct SystemClock {
+        origin: Instant,
+    }
+
+    impl SystemClock {
+        /// Clock with its origin now
+        pub fn new() -> Self {
+            Self {
+                origin: Instant::now(),
+            }
+        }
+    }
+
+    impl Default for SystemClock {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl super::Clock for SystemClock {
+        fn now_ms(&mut self) -> f64 {
+            self.origin.elapsed().as_secs_f64() * 1000.0
+        }
+    }
+}
+
+pub use platform::SystemClock;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Clock that advances a fixed amount every time it is read
+    pub(crate) struct TickingClock {
+        pub now: f64,
+        pub tick: f64,
+    }
+
+    impl Clock for TickingClock {
+        fn now_ms(&mut self) -> f64 {
+            self.now += self.tick;
+            self.now
+        }
+    }
+
+    #[test]
+    fn test_system_clock_is_monotonic() {
+        let mut clock = SystemClock::new();
+        let a = clock.now_ms();
+        let b = clock.now_ms();
+        assert!(a >= 0.0 && b >= a);
+    }
+}
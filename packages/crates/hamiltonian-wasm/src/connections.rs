@@ -0,0 +1,178 @@
+//! Road connections of a cell as a 4-bit mask
+//!
+//! Bit `i` stands for `DIRECTIONS[i]` (1 up, 2 down, 4 left, 8 right), the
+//! same layout as `Grid::walls`. A road cell has one connection at either end
+//! of the path and two everywhere else, so the mask also tells a renderer
+//! which tile to draw and how to turn it.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::DIRECTIONS;
+
+/// Set of directions a road cell connects to
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Connections(u8);
+
+impl Connections {
+    pub const NONE: Connections = Connections(0);
+    pub const UP: Connections = Connections(1);
+    pub const DOWN: Connections = Connections(2);
+    pub const LEFT: Connections = Connections(4);
+    pub const RIGHT: Connections = Connections(8);
+
+    /// Clockwise order, for rotations
+    const CLOCKWISE: [Connections; 4] = [Self::UP, Self::RIGHT, Self::DOWN, Self::LEFT];
+
+    /// Mask from raw bits; bits above the four directions are dropped
+    pub const fn from_bits(bits: u8) -> Self {
+        Connections(bits & 0xF)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// The single direction `DIRECTIONS[index]`
+    pub(crate) const fn from_direction(index: usize) -> Self {
+        Connections(1 << index)
+    }
+
+    pub const fn contains(self, other: Connections) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Connections) {
+        self.0 |= other.0;
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of connected directions
+    pub const fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// End of the road: exactly one connection
+    pub const fn is_endpoint(self) -> bool {
+        self.len() == 1
+    }
+
+    /// Passes straight through: up and down, or left and right
+    pub const fn is_straight(self) -> bool {
+        self.0 == Self::UP.0 | Self::DOWN.0 || self.0 == Self::LEFT.0 | Self::RIGHT.0
+    }
+
+    /// Bends: one vertical and one horizontal connection
+    pub const fn is_corner(self) -> bool {
+        self.len() == 2 && !self.is_straight()
+    }
+
+    /// The same mask turned a quarter clockwise
+    pub fn rotate_cw(self) -> Self {
+        let mut rotated = Self::NONE;
+        for (i, &direction) in Self::CLOCKWISE.iter().enumerate() {
+            if self.contains(direction) {
+                rotated.insert(Self::CLOCKWISE[(i + 1) % 4]);
+            }
+        }
+        rotated
+    }
+
+    /// Quarter turns clockwise from the tile's base orientation: up for an
+    /// endpoint, up-down for a straight, up-right for a corner. Other masks
+    /// are symmetric or not road tiles and report 0.
+    pub fn rotation(self) -> u8 {
+        let base = if self.is_endpoint() {
+            Self::UP
+        } else if self.is_straight() {
+            Connections(Self::UP.0 | Self::DOWN.0)
+        } else if self.is_corner() {
+            Connections(Self::UP.0 | Self::RIGHT.0)
+        } else {
+            return 0;
+        };
+        let mut turned = base;
+        for quarter in 0..4 {
+            if turned == self {
+                return quarter;
+            }
+            turned = turned.rotate_cw();
+        }
+        0
+    }
+
+    /// Names of the connected directions, in `DIRECTIONS` order
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        DIRECTIONS
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| self.contains(Self::from_direction(i)))
+            .map(|(_, &(_, _, name))| name)
+    }
+}
+
+impl std::ops::BitOr for Connections {
+    type Output = Connections;
+
+    fn bitor(self, other: Connections) -> Connections {
+        Connections(self.0 | other.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Connections {
+    /// Reads the raw bits; like `from_bits`, bits above the four directions
+    /// are dropped
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Self::from_bits)
+    }
+}
+
+impl fmt::Debug for Connections {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes() {
+        let vertical = Connections::UP | Connections::DOWN;
+        assert!(vertical.is_straight() && !vertical.is_corner() && !vertical.is_endpoint());
+        let bend = Connections::DOWN | Connections::LEFT;
+        assert!(bend.is_corner() && !bend.is_straight());
+        assert!(Connections::RIGHT.is_endpoint());
+        assert!(Connections::NONE.is_empty());
+        assert_eq!(Connections::from_bits(0xFF).bits(), 0xF);
+        assert_eq!(bend.names().collect::<Vec<_>>(), ["down", "left"]);
+        assert_eq!(format!("{:?}", bend), r#"{"down", "left"}"#);
+    }
+
+    #[test]
+    fn test_deserialize_drops_high_bits() {
+        let mask: Connections = serde_json::from_str("246").unwrap();
+        assert_eq!(mask, Connections::DOWN | Connections::LEFT);
+        assert_eq!(serde_json::to_string(&mask).unwrap(), "6");
+    }
+
+    #[test]
+    fn test_rotation() {
+        use Connections as C;
+        assert_eq!((C::UP | C::DOWN).rotation(), 0);
+        assert_eq!((C::LEFT | C::RIGHT).rotation(), 1);
+        assert_eq!((C::UP | C::RIGHT).rotation(), 0);
+        assert_eq!((C::RIGHT | C::DOWN).rotation(), 1);
+        assert_eq!((C::DOWN | C::LEFT).rotation(), 2);
+        assert_eq!((C::LEFT | C::UP).rotation(), 3);
+        assert_eq!(C::LEFT.rotation(), 3);
+        assert_eq!(C::UP.rotate_cw(), C::RIGHT);
+        assert_eq!(C::LEFT.rotate_cw(), C::UP);
+    }
+}
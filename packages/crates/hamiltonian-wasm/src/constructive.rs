@@ -0,0 +1,699 @@
+//! Constructive Hamiltonian path solver for full rectangular grids
+//!
+//! Rectangular grid graphs have an exact characterization of when a Hamiltonian
+//! path between two cells exists (Itai, Papadimitriou & Szwarcfiter, 1982).
+//! Instead of searching, this module decides existence in O(1) and then builds
+//! the path directly:
+//!
+//! 1. Peel 2-wide strips that contain neither endpoint off the rectangle as long
+//!    as the remainder stays solvable.
+//! 2. Split the remaining core between the endpoints into two solvable halves
+//!    joined by a single edge, and solve each half recursively.
+//! 3. Re-attach every peeled strip by replacing one path edge on the adjacent
+//!    boundary with a detour around the strip.
+//!
+//! The path is kept as a successor array over cell indices, so splicing a strip
+//! only touches the cells of that strip and the whole construction stays linear
+//! in the number of cells.
+
+use crate::{is_in_bounds, GridSize, InfeasibleReason, Point, DIRECTIONS};
+
+const NO_SUCCESSOR: u32 = u32::MAX;
+
+/// Cores no straight cut can split (only 4x5 with adjacent endpoints in
+/// practice) are small enough to be solved by exhaustive search
+const SMALL_CORE_AREA: i32 = 20;
+
+/// Axis-aligned sub-rectangle of the grid (absolute coordinates)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    row: i32,
+    col: i32,
+    rows: i32,
+    cols: i32,
+}
+
+impl Rect {
+    #[inline]
+    fn area(&self) -> i32 {
+        self.rows * self.cols
+    }
+
+    #[inline]
+    fn contains(&self, p: Point) -> bool {
+        p.row >= self.row
+            && p.row < self.row + self.rows
+            && p.col >= self.col
+            && p.col < self.col + self.cols
+    }
+}
+
+/// Side of a rectangle a strip is peeled from
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+const SIDES: [Side; 4] = [Side::Left, Side::Right, Side::Top, Side::Bottom];
+
+/// Check whether a Hamiltonian path from `s` to `t` exists inside `rect`.
+#[inline]
+fn is_acceptable(rect: &Rect, s: Point, t: Point) -> bool {
+    rect.contains(s) && rect.contains(t) && obstruction(rect, s, t).is_none()
+}
+
+/// Explain why no Hamiltonian path from `s` to `t` covers `rect`, if any.
+///
+/// A rectangle admits such a path iff the endpoints are color compatible and the
+/// configuration is none of the forbidden cases F1 (1-wide), F2 (2-wide) or
+/// F3 (3-wide with an even length). Both endpoints must lie inside `rect`.
+fn obstruction(rect: &Rect, s: Point, t: Point) -> Option<InfeasibleReason> {
+    if rect.area() == 1 {
+        return None;
+    }
+    if s == t {
+        return Some(InfeasibleReason::SameEndpoints);
+    }
+
+    // Work in local coordinates so colors are relative to the rectangle origin
+    let s = Point {
+        row: s.row - rect.row,
+        col: s.col - rect.col,
+    };
+    let t = Point {
+        row: t.row - rect.row,
+        col: t.col - rect.col,
+    };
+
+    let s_color = (s.row + s.col) % 2;
+    let t_color = (t.row + t.col) % 2;
+    if rect.area() % 2 == 0 {
+        if s_color == t_color {
+            return Some(InfeasibleReason::Parity);
+        }
+    } else if s_color != 0 || t_color != 0 {
+        // Odd rectangles have one more cell of the corner color; both
+        // endpoints must take that majority color
+        return Some(InfeasibleReason::ColorImbalance);
+    }
+
+    // Normalize so the rectangle is at most as tall as it is wide
+    let (height, width, s, t) = if rect.rows <= rect.cols {
+        (rect.rows, rect.cols, s, t)
+    } else {
+        let transpose = |p: Point| Point {
+            row: p.col,
+            col: p.row,
+        };
+        (rect.cols, rect.rows, transpose(s), transpose(t))
+    };
+
+    match height {
+        // F1: a single line can only be walked from one end to the other
+        1 if s.col.min(t.col) != 0 || s.col.max(t.col) != width - 1 => {
+            Some(InfeasibleReason::SingleLine)
+        }
+        // F2: endpoints may not form a non-boundary rung of a ladder
+        2 if s.col == t.col && s.col != 0 && s.col != width - 1 => {
+            Some(InfeasibleReason::LadderRung)
+        }
+        // F3: with the odd-colored endpoint `a` and the even-colored `b`, the
+        // cells left of `a` cannot be covered once `b` lies too far to the right
+        3 if width % 2 == 0 => {
+            let (a, b) = if s_color == 1 { (s, t) } else { (t, s) };
+            (b.col > a.col + 1 || (a.row == 1 && b.col > a.col))
+                .then_some(InfeasibleReason::ThreeWideTrap)
+        }
+        _ => None,
+    }
+}
+
+/// Builds a Hamiltonian path as a successor array over cell indices
+struct PathBuilder {
+    cols: i32,
+    next: Vec<u32>,
+}
+
+impl PathBuilder {
+    fn new(grid_size: &GridSize) -> Self {
+        Self {
+            cols: grid_size.cols,
+            next: vec![NO_SUCCESSOR; (grid_size.rows * grid_size.cols) as usize],
+        }
+    }
+
+    #[inline]
+    fn index(&self, p: Point) -> usize {
+        (p.row * self.cols + p.col) as usize
+    }
+
+    #[inline]
+    fn link(&mut self, from: Point, to: Point) {
+        let i = self.index(from);
+        self.next[i] = self.index(to) as u32;
+    }
+
+    #[inline]
+    fn follows(&self, from: Point, to: Point) -> bool {
+        self.next[self.index(from)] == self.index(to) as u32
+    }
+
+    /// Build a path from `s` to `t` covering `rect`. Requires `is_acceptable`.
+    fn build(&mut self, rect: Rect, s: Point, t: Point) -> bool {
+        let mut core = rect;
+        let mut peeled: Vec<(Side, Rect)> = Vec::new();
+
+        while let Some((side, rest)) = find_strip(&core, s, t) {
+            peeled.push((side, core));
+            core = rest;
+        }
+
+        if core.area() > 1 {
+            match find_split(&core, s, t) {
+                Some((first, p, second, q)) => {
+                    if !self.build(first, s, p) || !self.build(second, q, t) {
+                        return false;
+                    }
+                    self.link(p, q);
+                }
+                None if core.area() <= SMALL_CORE_AREA => {
+                    if !self.search(&core, s, t) {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+
+        for &(side, outer) in peeled.iter().rev() {
+            if !self.splice_strip(side, outer) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Exhaustive depth-first search for a path from `s` to `t` covering the
+    /// small `rect`, linking the result on success
+    fn search(&mut self, rect: &Rect, s: Point, t: Point) -> bool {
+        fn extend(rect: &Rect, t: Point, path: &mut Vec<Point>) -> bool {
+            let current = *path.last().unwrap();
+            if path.len() == rect.area() as usize {
+                return current == t;
+            }
+            if current == t {
+                return false;
+            }
+            for &(dr, dc, _) in &DIRECTIONS {
+                let next = Point {
+                    row: current.row + dr,
+                    col: current.col + dc,
+                };
+                if rect.contains(next) && !path.contains(&next) {
+                    path.push(next);
+                    if extend(rect, t, path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+            }
+            false
+        }
+
+        let mut path = vec![s];
+        if !extend(rect, t, &mut path) {
+            return false;
+        }
+        for pair in path.windows(2) {
+            self.link(pair[0], pair[1]);
+        }
+        true
+    }
+
+    /// Re-attach the strip on `side` of `outer`, whose remainder is already
+    /// covered, by detouring one boundary edge of the path around the strip.
+    fn splice_strip(&mut self, side: Side, outer: Rect) -> bool {
+        let length = match side {
+            Side::Left | Side::Right => outer.rows,
+            Side::Top | Side::Bottom => outer.cols,
+        };
+        let cell = |i: i32, depth: i32| strip_cell(side, &outer, i, depth);
+
+        // Find a path edge u -> v running along the boundary of the remainder
+        let edge = (0..length - 1).find_map(|i| {
+            let (a, b) = (cell(i, 0), cell(i + 1, 0));
+            if self.follows(a, b) {
+                Some((i, i + 1))
+            } else if self.follows(b, a) {
+                Some((i + 1, i))
+            } else {
+                None
+            }
+        });
+        let Some((u, v)) = edge else {
+            return false;
+        };
+
+        // The strip ring runs along the inner line and back along the outer
+        // line; walk it from u' to v' the long way round
+        let ring_len = 2 * length;
+        let ring_cell = |k: i32| {
+            if k < length {
+                cell(k, 1)
+            } else {
+                cell(ring_len - 1 - k, 2)
+            }
+        };
+        let step = if v == u + 1 { ring_len - 1 } else { 1 };
+
+        let mut prev = cell(u, 0);
+        let mut k = u;
+        for _ in 0..ring_len {
+            let current = ring_cell(k);
+            self.link(prev, current);
+            prev = current;
+            k = (k + step) % ring_len;
+        }
+        self.link(prev, cell(v, 0));
+
+        true
+    }
+
+    /// Walk the successor chain from `start` into a point list
+    fn into_path(self, start: Point, len: usize) -> Vec<Point> {
+        let mut path = Vec::with_capacity(len);
+        let mut current = self.index(start) as u32;
+        while current != NO_SUCCESSOR && path.len() < len {
+            path.push(Point {
+                row: current as i32 / self.cols,
+                col: current as i32 % self.cols,
+            });
+            current = self.next[current as usize];
+        }
+        path
+    }
+}
+
+/// Cell `i` along the strip on `side` of `outer`, at `depth` lines from the
+/// remainder: depth 0 is the boundary line of the remainder, 1 the inner line
+/// of the strip and 2 the outer border line.
+#[inline]
+fn strip_cell(side: Side, outer: &Rect, i: i32, depth: i32) -> Point {
+    match side {
+        Side::Left => Point {
+            row: outer.row + i,
+            col: outer.col + 2 - depth,
+        },
+        Side::Right => Point {
+            row: outer.row + i,
+            col: outer.col + outer.cols - 3 + depth,
+        },
+        Side::Top => Point {
+            row: outer.row + 2 - depth,
+            col: outer.col + i,
+        },
+        Side::Bottom => Point {
+            row: outer.row + outer.rows - 3 + depth,
+            col: outer.col + i,
+        },
+    }
+}
+
+/// Find a 2-wide strip without endpoints whose removal keeps the rest solvable
+fn find_strip(rect: &Rect, s: Point, t: Point) -> Option<(Side, Rect)> {
+    SIDES.iter().find_map(|&side| {
+        let (strip, rest, length) = match side {
+            Side::Left | Side::Right if rect.cols >= 3 && rect.rows >= 2 => {
+                let (strip_col, rest_col) = match side {
+                    Side::Left => (rect.col, rect.col + 2),
+                    _ => (rect.col + rect.cols - 2, rect.col),
+                };
+                (
+                    Rect {
+                        col: strip_col,
+                        cols: 2,
+                        ..*rect
+                    },
+                    Rect {
+                        col: rest_col,
+                        cols: rect.cols - 2,
+                        ..*rect
+                    },
+                    rect.rows,
+                )
+            }
+            Side::Top | Side::Bottom if rect.rows >= 3 && rect.cols >= 2 => {
+                let (strip_row, rest_row) = match side {
+                    Side::Top => (rect.row, rect.row + 2),
+                    _ => (rect.row + rect.rows - 2, rect.row),
+                };
+                (
+                    Rect {
+                        row: strip_row,
+                        rows: 2,
+                        ..*rect
+                    },
+                    Rect {
+                        row: rest_row,
+                        rows: rect.rows - 2,
+                        ..*rect
+                    },
+                    rect.cols,
+                )
+            }
+            _ => return None,
+        };
+
+        if strip.contains(s) || strip.contains(t) || !is_acceptable(&rest, s, t) {
+            return None;
+        }
+
+        // A boundary line of two cells holding both endpoints carries no path
+        // edge to detour through the strip
+        if length == 2 {
+            let on_boundary = |p: Point| match side {
+                Side::Left => p.col == rest.col,
+                Side::Right => p.col == rest.col + rest.cols - 1,
+                Side::Top => p.row == rest.row,
+                Side::Bottom => p.row == rest.row + rest.rows - 1,
+            };
+            if on_boundary(s) && on_boundary(t) {
+                return None;
+            }
+        }
+
+        Some((side, rest))
+    })
+}
+
+/// Split `rect` between `s` and `t` into two solvable halves.
+///
+/// Returns `(first, p, second, q)` where the first half is covered from `s` to
+/// `p`, the second from `q` to `t`, and `p`/`q` are adjacent across the cut.
+fn find_split(rect: &Rect, s: Point, t: Point) -> Option<(Rect, Point, Rect, Point)> {
+    let vertical = split_along(rect, s, t, false);
+    vertical.or_else(|| split_along(rect, s, t, true))
+}
+
+fn split_along(
+    rect: &Rect,
+    s: Point,
+    t: Point,
+    horizontal: bool,
+) -> Option<(Rect, Point, Rect, Point)> {
+    // Work on the transposed problem for horizontal cuts
+    let flip = |p: Point| {
+        if horizontal {
+            Point {
+                row: p.col,
+                col: p.row,
+            }
+        } else {
+            p
+        }
+    };
+    let flip_rect = |r: Rect| {
+        if horizontal {
+            Rect {
+                row: r.col,
+                col: r.row,
+                rows: r.cols,
+                cols: r.rows,
+            }
+        } else {
+            r
+        }
+    };
+
+    let area = flip_rect(*rect);
+    let (fs, ft) = (flip(s), flip(t));
+    if fs.col == ft.col {
+        return None;
+    }
+
+    // Try cuts closest to the middle first to keep the recursion shallow
+    let (lo, hi) = (fs.col.min(ft.col) + 1, fs.col.max(ft.col));
+    let mid = (lo + hi) / 2;
+    let mut cuts: Vec<i32> = (lo..=hi).collect();
+    cuts.sort_by_key(|&x| (x - mid).abs());
+
+    for cut in cuts {
+        let left = Rect {
+            cols: cut - area.col,
+            ..area
+        };
+        let right = Rect {
+            col: cut,
+            cols: area.col + area.cols - cut,
+            ..area
+        };
+        let (first, second, p_col, q_col) = if fs.col < cut {
+            (left, right, cut - 1, cut)
+        } else {
+            (right, left, cut, cut - 1)
+        };
+
+        for row in area.row..area.row + area.rows {
+            let p = Point { row, col: p_col };
+            let q = Point { row, col: q_col };
+            let (first, second) = (flip_rect(first), flip_rect(second));
+            let (p, q) = (flip(p), flip(q));
+            if is_acceptable(&first, s, p) && is_acceptable(&second, q, t) {
+                return Some((first, p, second, q));
+            }
+        }
+    }
+
+    None
+}
+
+/// Explain why no Hamiltonian path between `start` and `end` covers the full
+/// `grid_size` rectangle, or `None` if one exists. Exact and O(1); both points
+/// must be in bounds.
+pub(crate) fn rectangle_obstruction(
+    start: Point,
+    end: Point,
+    grid_size: &GridSize,
+) -> Option<InfeasibleReason> {
+    let rect = Rect {
+        row: 0,
+        col: 0,
+        rows: grid_size.rows,
+        cols: grid_size.cols,
+    };
+    if start == end {
+        return Some(InfeasibleReason::SameEndpoints);
+    }
+    obstruction(&rect, start, end)
+}
+
+/// Check whether a Hamiltonian path between `start` and `end` exists on the
+/// full `grid_size` rectangle
+pub(crate) fn has_hamiltonian_path(start: Point, end: Point, grid_size: &GridSize) -> bool {
+    is_in_bounds(start.row, start.col, grid_size)
+        && is_in_bounds(end.row, end.col, grid_size)
+        && rectangle_obstruction(start, end, grid_size).is_none()
+}
+
+/// Construct a Hamiltonian path from `start` to `end` on the full rectangle.
+///
+/// Returns `None` exactly when no such path exists.
+pub(crate) fn construct_path(start: Point, end: Point, grid_size: &GridSize) -> Option<Vec<Point>> {
+    if !has_hamiltonian_path(start, end, grid_size) {
+        return None;
+    }
+
+    let rect = Rect {
+        row: 0,
+        col: 0,
+        rows: grid_size.rows,
+        cols: grid_size.cols,
+    };
+    let mut builder = PathBuilder::new(grid_size);
+    if !builder.build(rect, start, end) {
+        return None;
+    }
+
+    let total_cells = (grid_size.rows * grid_size.cols) as usize;
+    let path = builder.into_path(start, total_cells);
+    (path.len() == total_cells).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Exhaustive search used as ground truth on small grids
+    fn brute_force_exists(start: Point, end: Point, grid_size: &GridSize) -> bool {
+        fn go(
+            current: Point,
+            end: Point,
+            grid_size: &GridSize,
+            visited: &mut [bool],
+            left: usize,
+        ) -> bool {
+            if left == 0 {
+                return current == end;
+            }
+            if current == end {
+                return false;
+            }
+            for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let next = Point {
+                    row: current.row + dr,
+                    col: current.col + dc,
+                };
+                if next.row < 0
+                    || next.row >= grid_size.rows
+                    || next.col < 0
+                    || next.col >= grid_size.cols
+                {
+                    continue;
+                }
+                let i = (next.row * grid_size.cols + next.col) as usize;
+                if visited[i] {
+                    continue;
+                }
+                visited[i] = true;
+                if go(next, end, grid_size, visited, left - 1) {
+                    return true;
+                }
+                visited[i] = false;
+            }
+            false
+        }
+
+        if start == end {
+            return false;
+        }
+        let total_cells = (grid_size.rows * grid_size.cols) as usize;
+        let mut visited = vec![false; total_cells];
+        visited[(start.row * grid_size.cols + start.col) as usize] = true;
+        go(start, end, grid_size, &mut visited, total_cells - 1)
+    }
+
+    fn assert_valid_path(path: &[Point], start: Point, end: Point, grid_size: &GridSize) {
+        let total_cells = (grid_size.rows * grid_size.cols) as usize;
+        assert_eq!(path.len(), total_cells);
+        assert_eq!(path[0], start);
+        assert_eq!(path[total_cells - 1], end);
+
+        let unique: HashSet<Point> = path.iter().copied().collect();
+        assert_eq!(unique.len(), total_cells, "Duplicate cell in path");
+
+        for pair in path.windows(2) {
+            let dr = (pair[1].row - pair[0].row).abs();
+            let dc = (pair[1].col - pair[0].col).abs();
+            assert_eq!(dr + dc, 1, "Non-adjacent cells in path");
+        }
+    }
+
+    fn all_cells(grid_size: &GridSize) -> Vec<Point> {
+        (0..grid_size.rows)
+            .flat_map(|row| (0..grid_size.cols).map(move |col| Point { row, col }))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_brute_force_on_small_grids() {
+        for rows in 1..=5 {
+            for cols in 1..=5 {
+                let grid_size = GridSize { rows, cols };
+                let cells = all_cells(&grid_size);
+                for &start in &cells {
+                    for &end in &cells {
+                        let expected = brute_force_exists(start, end, &grid_size);
+                        assert_eq!(
+                            has_hamiltonian_path(start, end, &grid_size),
+                            expected,
+                            "{}x{} ({},{}) -> ({},{})",
+                            rows,
+                            cols,
+                            start.row,
+                            start.col,
+                            end.row,
+                            end.col
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_wide_forbidden_configurations() {
+        for (rows, cols) in [(3, 2), (3, 4), (3, 6), (6, 3)] {
+            let grid_size = GridSize { rows, cols };
+            let cells = all_cells(&grid_size);
+            for &start in &cells {
+                for &end in &cells {
+                    assert_eq!(
+                        has_hamiltonian_path(start, end, &grid_size),
+                        brute_force_exists(start, end, &grid_size),
+                        "{}x{} ({},{}) -> ({},{})",
+                        rows,
+                        cols,
+                        start.row,
+                        start.col,
+                        end.row,
+                        end.col
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_constructs_every_solvable_pair() {
+        for rows in 1..=8 {
+            for cols in 1..=8 {
+                let grid_size = GridSize { rows, cols };
+                let cells = all_cells(&grid_size);
+                for &start in &cells {
+                    for &end in &cells {
+                        match construct_path(start, end, &grid_size) {
+                            Some(path) => assert_valid_path(&path, start, end, &grid_size),
+                            None => assert!(!has_hamiltonian_path(start, end, &grid_size)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_grids() {
+        let cases = [
+            (
+                GridSize { rows: 20, cols: 40 },
+                Point { row: 0, col: 0 },
+                Point { row: 0, col: 1 },
+            ),
+            (
+                GridSize { rows: 20, cols: 40 },
+                Point { row: 10, col: 17 },
+                Point { row: 3, col: 31 },
+            ),
+            (
+                GridSize { rows: 10, cols: 10 },
+                Point { row: 0, col: 0 },
+                Point { row: 0, col: 1 },
+            ),
+            (
+                GridSize { rows: 33, cols: 17 },
+                Point { row: 16, col: 8 },
+                Point { row: 0, col: 0 },
+            ),
+        ];
+
+        for (grid_size, start, end) in cases {
+            let path = construct_path(start, end, &grid_size).expect("solvable pair");
+            assert_valid_path(&path, start, end, &grid_size);
+        }
+    }
+}
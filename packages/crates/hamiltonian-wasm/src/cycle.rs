@@ -0,0 +1,154 @@
+//! Hamiltonian cycles (closed loop roads)
+//!
+//! A grid graph is bipartite, so a cycle through every open cell needs as many
+//! cells of each checkerboard color, and in particular an even cell count. On a
+//! full rectangle that is also sufficient as long as both sides are at least 2,
+//! and the loop is built directly; grids with obstacles fall back to searching.
+
+use crate::grid::{is_open_region_connected, Grid};
+use crate::{GridSize, InfeasibleReason, Point};
+
+/// Explain why no Hamiltonian cycle covers the open cells of `grid`, if any.
+///
+/// Exact for full rectangles; for grids with obstacles `None` only means no
+/// obstruction was detected.
+pub(crate) fn cycle_obstruction(grid: &Grid) -> Option<InfeasibleReason> {
+    if !grid.open_cells().is_multiple_of(2) {
+        return Some(InfeasibleReason::OddCellCount);
+    }
+    if grid.is_full_rectangle() {
+        // A single row or column cannot be closed into a loop
+        return (grid.rows < 2 || grid.cols < 2).then_some(InfeasibleReason::SingleLine);
+    }
+
+    let even = grid
+        .open_points()
+        .filter(|p| (p.row + p.col) % 2 == 0)
+        .count();
+    if even * 2 != grid.open_cells() {
+        return Some(InfeasibleReason::ColorImbalance);
+    }
+
+    // Every cell on a loop has exactly two loop neighbors
+    if grid
+        .open_points()
+        .any(|p| grid.open_degree(p.row, p.col) < 2)
+    {
+        return Some(InfeasibleReason::DeadEnd);
+    }
+
+    if !is_open_region_connected(grid) {
+        return Some(InfeasibleReason::Disconnected);
+    }
+
+    None
+}
+
+/// Build a Hamiltonian cycle of a full rectangle starting at `start`.
+///
+/// Requires `cycle_obstruction` to report nothing for the rectangle. The loop
+/// runs along the first row, snakes back through the remaining rows and returns
+/// up the first column; it is then rotated to begin at `start`.
+pub(crate) fn construct_cycle(start: Point, grid_size: &GridSize) -> Vec<Point> {
+    // Snake along the dimension with an even count so the return lane is free
+    let transpose = grid_size.rows % 2 != 0;
+    let (rows, cols) = if transpose {
+        (grid_size.cols, grid_size.rows)
+    } else {
+        (grid_size.rows, grid_size.cols)
+    };
+
+    let mut cycle = Vec::with_capacity((rows * cols) as usize);
+    cycle.extend((0..cols).map(|col| Point { row: 0, col }));
+    for row in 1..rows {
+        if row % 2 == 1 {
+            cycle.extend((1..cols).rev().map(|col| Point { row, col }));
+        } else {
+            cycle.extend((1..cols).map(|col| Point { row, col }));
+        }
+    }
+    cycle.extend((1..rows).rev().map(|row| Point { row, col: 0 }));
+
+    if transpose {
+        for p in &mut cycle {
+            *p = Point {
+                row: p.col,
+                col: p.row,
+            };
+        }
+    }
+
+    let offset = cycle.iter().position(|&p| p == start).unwrap_or(0);
+    cycle.rotate_left(offset);
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn assert_valid_cycle(cycle: &[Point], grid_size: &GridSize) {
+        let total_cells = (grid_size.rows * grid_size.cols) as usize;
+        assert_eq!(cycle.len(), total_cells);
+
+        let unique: HashSet<Point> = cycle.iter().copied().collect();
+        assert_eq!(unique.len(), total_cells, "Duplicate cell in cycle");
+
+        for i in 0..cycle.len() {
+            let (a, b) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            let dr = (a.row - b.row).abs();
+            let dc = (a.col - b.col).abs();
+            assert_eq!(dr + dc, 1, "Non-adjacent cells in cycle");
+        }
+    }
+
+    #[test]
+    fn test_construct_cycle_on_even_rectangles() {
+        for rows in 2..=7 {
+            for cols in 2..=7 {
+                let grid_size = GridSize { rows, cols };
+                if (rows * cols) % 2 != 0 {
+                    assert_eq!(
+                        cycle_obstruction(&grid_size.into()),
+                        Some(InfeasibleReason::OddCellCount)
+                    );
+                    continue;
+                }
+                assert_eq!(cycle_obstruction(&grid_size.into()), None);
+
+                let start = Point {
+                    row: rows - 1,
+                    col: cols / 2,
+                };
+                let cycle = construct_cycle(start, &grid_size);
+                assert_eq!(cycle[0], start);
+                assert_valid_cycle(&cycle, &grid_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cycle_obstructions() {
+        let p = |row, col| Point { row, col };
+
+        assert_eq!(
+            cycle_obstruction(&Grid::new(1, 4)),
+            Some(InfeasibleReason::SingleLine)
+        );
+        // Blocking an edge cell of a 3x3 leaves 8 cells, 5 of them one color
+        assert_eq!(
+            cycle_obstruction(&Grid::new(3, 3).with_blocked(&[p(0, 1)]).unwrap()),
+            Some(InfeasibleReason::ColorImbalance)
+        );
+        // A cell hanging off the loop cannot be entered and left
+        assert_eq!(
+            cycle_obstruction(&Grid::new(3, 4).with_blocked(&[p(1, 0), p(2, 2)]).unwrap()),
+            Some(InfeasibleReason::DeadEnd)
+        );
+        assert_eq!(
+            cycle_obstruction(&Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap()),
+            None
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Compact string encodings of a path
+//!
+//! A path is stored as its first cell and the moves after it, one letter per
+//! step in `DIRECTIONS` order (`U`, `D`, `L`, `R`):
+//!
+//! - `Moves`: `"2,0:RRUULD"`
+//! - `RunLength`: the same with repeated moves counted, `"2,0:2R2ULD"`
+//! - `Base64`: unpadded base64url of the start row, start column and move
+//!   count as LEB128 varints followed by the moves packed four to a byte,
+//!   high bits first. About a quarter of a character per cell, for URLs.
+//!
+//! Decoding accepts any of the three (run lengths are optional in the text
+//! form) and checks the result is a simple path on the grid.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::{
+    direction_between, validate_grid, validate_path, validate_point, Point, DIRECTIONS,
+    MAX_GRID_CELLS,
+};
+
+/// Move letters, indexed like `DIRECTIONS`
+const MOVES: [u8; 4] = *b"UDLR";
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// String form produced by `encode_path`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PathEncoding {
+    /// Start cell and one letter per move
+    #[default]
+    Moves,
+    /// Start cell and moves with runs of two or more counted
+    RunLength,
+    /// Packed binary form as base64url
+    Base64,
+}
+
+/// Encode a path of adjacent cells. The empty path encodes as `""`.
+pub fn encode_path(path: &[Point], encoding: PathEncoding) -> Result<String, SolveError> {
+    let Some(&start) = path.first() else {
+        return Ok(String::new());
+    };
+    if start.row < 0 || start.col < 0 {
+        return Err(SolveError::InvalidPath {
+            index: 0,
+            reason: "negative coordinate",
+        });
+    }
+    let moves = path
+        .windows(2)
+        .enumerate()
+        .map(|(i, step)| {
+            direction_between(step[0], step[1]).ok_or(SolveError::InvalidPath {
+                index: i + 1,
+                reason: "step to a non-adjacent cell",
+            })
+        })
+        .collect::<Result<Vec<usize>, SolveError>>()?;
+
+    let text = |run_length: bool| {
+        let mut out = format!("{},{}:", start.row, start.col);
+        let mut i = 0;
+        while i < moves.len() {
+            let run = if run_length {
+                moves[i..].iter().take_while(|&&m| m == moves[i]).count()
+            } else {
+                1
+            };
+            if run > 1 {
+                out.push_str(&run.to_string());
+            }
+            out.push(MOVES[moves[i]] as char);
+            i += run;
+        }
+        out
+    };
+
+    Ok(match encoding {
+        PathEncoding::Moves => text(false),
+        PathEncoding::RunLength => text(true),
+        PathEncoding::Base64 => {
+            let mut bytes = Vec::with_capacity(moves.len() / 4 + 8);
+            for value in [start.row as u64, start.col as u64, moves.len() as u64] {
+                write_varint(&mut bytes, value);
+            }
+            for chunk in moves.chunks(4) {
+                let packed = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (i, &m)| byte | (m as u8) << (6 - 2 * i));
+                bytes.push(packed);
+            }
+            base64url_encode(&bytes)
+        }
+    })
+}
+
+/// Decode any `PathEncoding` and check the path is a simple path of
+/// adjacent open cells of `grid`. `""` decodes to the empty path.
+pub fn decode_path(encoded: &str, grid: &Grid) -> Result<Vec<Point>, SolveError> {
+    if encoded.is_empty() {
+        return Ok(Vec::new());
+    }
+    validate_grid(grid, MAX_GRID_CELLS)?;
+    // A simple path makes at most one move per cell after the first
+    let max_moves = grid.rows as usize * grid.cols as usize - 1;
+
+    let (start, moves) = match encoded.split_once(':') {
+        Some((start, moves)) => {
+            let start = parse_start(start)?;
+            let offset = encoded.len() - moves.len();
+            (start, parse_moves(moves, offset, max_moves)?)
+        }
+        None => unpack(&base64url_decode(encoded)?, max_moves)?,
+    };
+
+    // Bounding the start keeps the walk below clear of overflow
+    validate_point(start, grid)?;
+    let mut path = Vec::with_capacity(moves.len() + 1);
+    path.push(start);
+    let mut here = start;
+    for direction in moves {
+        let (dr, dc, _) = DIRECTIONS[direction];
+        here = Point {
+            row: here.row + dr,
+            col: here.col + dc,
+        };
+        path.push(here);
+    }
+    validate_path(&path, grid)?;
+    Ok(path)
+}
+
+fn invalid(index: usize, reason: &'static str) -> SolveError {
+    SolveError::InvalidEncoding { index, reason }
+}
+
+/// `"row,col"` with plain decimal numbers
+fn parse_start(text: &str) -> Result<Point, SolveError> {
+    let number = |s: &str| {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse::<i32>().ok()
+    };
+    text.split_once(',')
+        .and_then(|(row, col)| {
+            Some(Point {
+                row: number(row)?,
+                col: number(col)?,
+            })
+        })
+        .ok_or(invalid(0, "malformed start cell"))
+}
+
+/// Move letters with optional run lengths; `offset` is the position of
+/// `text` in the whole string, for error positions
+fn parse_moves(text: &str, offset: usize, max_moves: usize) -> Result<Vec<usize>, SolveError> {
+    let mut moves = Vec::new();
+    let mut run: Option<usize> = None;
+    for (i, byte) in text.bytes().enumerate() {
+        let at = offset + i;
+        if byte.is_ascii_digit() {
+            let digit = (byte - b'0') as usize;
+            run = Some(
+                run.unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit))
+                    .filter(|&n| n <= max_moves)
+                    .ok_or(invalid(at, "path longer than the grid"))?,
+            );
+            continue;
+        }
+        let direction = MOVES
+            .iter()
+            .position(|&m| m == byte)
+            .ok_or(invalid(at, "unknown move"))?;
+        let count = match run.take() {
+            Some(0) => return Err(invalid(at, "zero run length")),
+            Some(n) => n,
+            None => 1,
+        };
+        if moves.len() + count > max_moves {
+            return Err(invalid(at, "path longer than the grid"));
+        }
+        moves.resize(moves.len() + count, direction);
+    }
+    if run.is_some() {
+        return Err(invalid(offset + text.len(), "run length without a move"));
+    }
+    Ok(moves)
+}
+
+/// Start cell and moves from the packed binary form
+fn unpack(bytes: &[u8], max_moves: usize) -> Result<(Point, Vec<usize>), SolveError> {
+    // Error positions are reported in characters of the base64url text
+    let at = |byte: usize| byte * 4 / 3;
+    let mut pos = 0;
+    let mut header = [0u64; 3];
+    for value in &mut header {
+        *value = read_varint(bytes, &mut pos).ok_or(invalid(at(pos), "truncated header"))?;
+    }
+    let [row, col, count] = header;
+    let coordinate = |v: u64| i32::try_from(v).map_err(|_| invalid(0, "malformed start cell"));
+    let start = Point {
+        row: coordinate(row)?,
+        col: coordinate(col)?,
+    };
+    let count = usize::try_from(count)
+        .ok()
+        .filter(|&n| n <= max_moves)
+        .ok_or(invalid(at(pos), "path longer than the grid"))?;
+
+    let packed = &bytes[pos..];
+    let needed = count.div_ceil(4);
+    if packed.len() < needed {
+        return Err(invalid(at(bytes.len()), "truncated moves"));
+    }
+    if packed.len() > needed {
+        return Err(invalid(at(pos + needed), "trailing data"));
+    }
+    let moves = (0..count)
+        .map(|i| (packed[i / 4] >> (6 - 2 * (i % 4)) & 3) as usize)
+        .collect();
+    Ok((start, moves))
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &b)| bits | (b as u32) << (16 - 8 * i));
+        // n bytes carry n + 1 characters' worth of bits
+        for i in 0..=chunk.len() {
+            out.push(BASE64URL[(bits >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64url_decode(text: &str) -> Result<Vec<u8>, SolveError> {
+    if text.len() % 4 == 1 {
+        return Err(invalid(text.len() - 1, "truncated base64url"));
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for (c, chunk) in text.as_bytes().chunks(4).enumerate() {
+        let mut bits = 0u32;
+        for (i, &byte) in chunk.iter().enumerate() {
+            let value = BASE64URL
+                .iter()
+                .position(|&b| b == byte)
+                .ok_or(invalid(c * 4 + i, "invalid base64url character"))?;
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            bytes.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{p, snake};
+
+    #[test]
+    fn test_text_forms() {
+        let path = snake(3, 3);
+        assert_eq!(
+            encode_path(&path, PathEncoding::Moves).unwrap(),
+            "0,0:RRDLLDRR"
+        );
+        assert_eq!(
+            encode_path(&path, PathEncoding::RunLength).unwrap(),
+            "0,0:2RD2LD2R"
+        );
+        let grid = Grid::new(3, 3);
+        assert_eq!(decode_path("0,0:RRDLLDRR", &grid).unwrap(), path);
+        assert_eq!(decode_path("0,0:2RD2LD2R", &grid).unwrap(), path);
+        assert_eq!(decode_path("1,1:", &grid).unwrap(), [p(1, 1)]);
+        assert_eq!(encode_path(&[], PathEncoding::Base64).unwrap(), "");
+        assert!(decode_path("", &grid).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        let grid = Grid::new(20, 30);
+        let path = snake(20, 30);
+        let encoded = encode_path(&path, PathEncoding::Base64).unwrap();
+        // 600 cells in a few over 200 characters
+        assert_eq!(encoded.len(), 206);
+        assert!(encoded
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(decode_path(&encoded, &grid).unwrap(), path);
+
+        // Every padding length decodes
+        for len in 1..8 {
+            let path = &path[..len];
+            let encoded = encode_path(path, PathEncoding::Base64).unwrap();
+            assert_eq!(decode_path(&encoded, &grid).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn test_decoding_rejects_bad_input() {
+        let grid = Grid::new(3, 3);
+        let encoding = |s: &str| match decode_path(s, &grid) {
+            Err(SolveError::InvalidEncoding { index, reason }) => Some((index, reason)),
+            _ => None,
+        };
+        assert_eq!(encoding("0,0:RRX"), Some((6, "unknown move")));
+        assert_eq!(encoding("0,0:RR3"), Some((7, "run length without a move")));
+        assert_eq!(encoding("0,0:0R"), Some((5, "zero run length")));
+        assert_eq!(
+            encoding("0,0:99999999999999999999R"),
+            Some((4, "path longer than the grid"))
+        );
+        assert_eq!(encoding("x,0:R"), Some((0, "malformed start cell")));
+        assert_eq!(encoding("-1,0:R"), Some((0, "malformed start cell")));
+        assert_eq!(encoding("AA*A"), Some((2, "invalid base64url character")));
+
+        assert!(matches!(
+            decode_path("0,0:RRR", &grid),
+            Err(SolveError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            decode_path("0,0:RLR", &grid),
+            Err(SolveError::InvalidPath { index: 2, .. })
+        ));
+        assert!(matches!(
+            decode_path("0,0:RD", &Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap()),
+            Err(SolveError::BlockedCell { .. })
+        ));
+        assert!(matches!(
+            encode_path(&[p(0, 0), p(1, 1)], PathEncoding::Moves),
+            Err(SolveError::InvalidPath { index: 1, .. })
+        ));
+    }
+}
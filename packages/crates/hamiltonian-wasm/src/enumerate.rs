@@ -0,0 +1,158 @@
+//! Enumeration of every Hamiltonian path between two cells
+//!
+//! Runs the path finder's search (`solver::Search`) and, instead of stopping
+//! at the first hit, resumes it after every path, so the pruning is shared
+//! with the path finder and paths are produced one at a time.
+
+use crate::grid::Grid;
+use crate::solver::{Search, SolverStatus};
+use crate::{InfeasibleReason, Point};
+
+/// Lazy iterator over all Hamiltonian paths from `start` to `end`.
+///
+/// Paths are produced in the search's depth-first order. The number of paths
+/// grows exponentially, so this is meant for small grids; use the frontier
+/// counter when only the count is needed.
+pub struct HamiltonianPaths {
+    search: Search,
+    /// Cells the search may enter after the start cell
+    max_steps: u32,
+}
+
+impl HamiltonianPaths {
+    /// Endpoints must be distinct open cells of `grid`
+    pub(crate) fn new(start: Point, end: Point, grid: &Grid) -> Self {
+        Self::over(Search::new(start, end, grid, None))
+    }
+
+    /// Paths from `start` that end on any cell
+    pub(crate) fn from_start(start: Point, grid: &Grid) -> Self {
+        Self::over(Search::from_start(start, grid))
+    }
+
+    /// Iterator that yields nothing, for requests known to have no path
+    pub(crate) fn ruled_out(grid: &Grid, reason: InfeasibleReason) -> Self {
+        Self::over(Search::ruled_out(grid, reason))
+    }
+
+    fn over(search: Search) -> Self {
+        Self {
+            search,
+            max_steps: u32::MAX,
+        }
+    }
+
+    /// Stop producing paths after entering `max_steps` cells
+    pub(crate) fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Whether every path has been produced, as opposed to the step budget
+    /// running out first
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.search.status() == SolverStatus::Exhausted
+    }
+}
+
+impl Iterator for HamiltonianPaths {
+    type Item = Vec<Point>;
+
+    fn next(&mut self) -> Option<Vec<Point>> {
+        self.search.resume();
+        loop {
+            // The start cell does not count as a step
+            let entered = self.search.iterations().saturating_sub(1);
+            let remaining = self.max_steps.saturating_sub(entered);
+            if remaining == 0 {
+                return None;
+            }
+            // A slice of moves enters at most as many cells
+            match self.search.step(remaining) {
+                SolverStatus::Found => return Some(self.search.path().to_vec()),
+                SolverStatus::Running => {}
+                SolverStatus::Exhausted | SolverStatus::Cancelled => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    #[test]
+    fn test_corner_to_corner_counts() {
+        // OEIS A001184 for odd n; opposite corners of an even grid share a color
+        for (n, expected) in [(2, 0), (3, 2), (4, 0), (5, 104)] {
+            let grid = Grid::new(n, n);
+            let count = HamiltonianPaths::new(p(0, 0), p(n - 1, n - 1), &grid).count();
+            assert_eq!(count, expected, "{}x{}", n, n);
+        }
+    }
+
+    #[test]
+    fn test_paths_are_distinct_and_hamiltonian() {
+        let grid = Grid::new(4, 4).with_blocked(&[p(1, 1)]).unwrap();
+        let paths: Vec<Vec<Point>> = HamiltonianPaths::new(p(0, 1), p(3, 2), &grid).collect();
+        assert!(!paths.is_empty());
+
+        let unique: HashSet<&Vec<Point>> = paths.iter().collect();
+        assert_eq!(unique.len(), paths.len());
+        for path in &paths {
+            assert_eq!(path.len(), grid.open_cells());
+            assert_eq!((path[0], path[path.len() - 1]), (p(0, 1), p(3, 2)));
+            assert!(path.iter().all(|c| grid.is_open(c.row, c.col)));
+            let cells: HashSet<&Point> = path.iter().collect();
+            assert_eq!(cells.len(), path.len());
+        }
+    }
+
+    #[test]
+    fn test_free_end_and_step_budget() {
+        // From a corner of a 3x3 every other even cell can end a path
+        let ends: HashSet<Point> = HamiltonianPaths::from_start(p(0, 0), &Grid::new(3, 3))
+            .map(|path| path[8])
+            .collect();
+        assert_eq!(ends, HashSet::from([p(0, 2), p(1, 1), p(2, 0), p(2, 2)]));
+
+        let mut paths =
+            HamiltonianPaths::new(p(0, 0), p(0, 1), &Grid::new(6, 6)).with_max_steps(10);
+        assert_eq!(paths.next(), None);
+        assert!(!paths.is_exhausted());
+    }
+
+    #[test]
+    fn test_step_budget_is_exact() {
+        // The first path takes some number of steps; one step fewer finds
+        // nothing, however often it is asked
+        let grid = Grid::new(4, 4).with_blocked(&[p(1, 1)]).unwrap();
+        let capped = |steps| HamiltonianPaths::new(p(0, 1), p(3, 2), &grid).with_max_steps(steps);
+        let first = HamiltonianPaths::new(p(0, 1), p(3, 2), &grid)
+            .next()
+            .unwrap();
+        let needed = (1..).find(|&steps| capped(steps).next().is_some()).unwrap();
+        assert_eq!(capped(needed).next(), Some(first));
+
+        let mut short = capped(needed - 1);
+        assert_eq!(short.next(), None);
+        assert_eq!(short.next(), None);
+        assert!(!short.is_exhausted());
+    }
+
+    #[test]
+    fn test_lazy_and_empty() {
+        let mut paths = HamiltonianPaths::new(p(0, 0), p(0, 1), &Grid::new(6, 6));
+        let first = paths.next().unwrap();
+        assert_eq!(first.len(), 36);
+        assert!(paths.next().is_some());
+
+        let ruled_out = HamiltonianPaths::ruled_out(&Grid::new(3, 3), InfeasibleReason::Parity);
+        assert_eq!(ruled_out.count(), 0);
+    }
+}
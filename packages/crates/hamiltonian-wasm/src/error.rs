@@ -0,0 +1,109 @@
+//! Error type shared by the native API and the WASM exports
+
+use std::fmt;
+
+use crate::{GridSize, Point};
+
+/// Reasons a request is rejected before (or instead of) solving it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// Grid dimensions must both be at least 1
+    InvalidGridSize { grid_size: GridSize },
+    /// The grid has more cells than the solver supports
+    GridTooLarge { cells: usize, max_cells: usize },
+    /// The narrower grid side exceeds what the path counter supports
+    GridTooWide { width: i32, max_width: i32 },
+    /// The obstacle mask does not have one entry per cell
+    InvalidObstacleMask { expected: usize, actual: usize },
+    /// The wall mask does not have one entry per cell
+    InvalidWallMask { expected: usize, actual: usize },
+    /// A start, end or path point lies outside the grid
+    OutOfBounds { point: Point, grid_size: GridSize },
+    /// A start, end or path point lies on a blocked cell
+    BlockedCell { point: Point },
+    /// Start and end are the same cell
+    SameEndpoints { point: Point },
+    /// A supplied path is not a simple path of adjacent cells
+    InvalidPath { index: usize, reason: &'static str },
+    /// A waypoint repeats another one or is an endpoint
+    InvalidWaypoint { index: usize, reason: &'static str },
+    /// A fixed edge is malformed or conflicts with the other constraints
+    InvalidFixedEdge { index: usize, reason: &'static str },
+    /// A wall does not join two adjacent cells
+    InvalidWall { index: usize, reason: &'static str },
+    /// An encoded path is malformed; `index` is the character position
+    InvalidEncoding { index: usize, reason: &'static str },
+    /// Converting between Rust and JavaScript values failed
+    SerializationFailed(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InvalidGridSize { grid_size } => write!(
+                f,
+                "InvalidGridSize: {}x{} grid must have at least one row and column",
+                grid_size.rows, grid_size.cols
+            ),
+            SolveError::GridTooLarge { cells, max_cells } => write!(
+                f,
+                "GridTooLarge: {} cells exceeds the maximum of {}",
+                cells, max_cells
+            ),
+            SolveError::GridTooWide { width, max_width } => write!(
+                f,
+                "GridTooWide: narrower side {} exceeds the maximum of {}",
+                width, max_width
+            ),
+            SolveError::InvalidObstacleMask { expected, actual } => write!(
+                f,
+                "InvalidObstacleMask: expected {} entries, got {}",
+                expected, actual
+            ),
+            SolveError::InvalidWallMask { expected, actual } => write!(
+                f,
+                "InvalidWallMask: expected {} entries, got {}",
+                expected, actual
+            ),
+            SolveError::OutOfBounds { point, grid_size } => write!(
+                f,
+                "OutOfBounds: ({}, {}) is outside the {}x{} grid",
+                point.row, point.col, grid_size.rows, grid_size.cols
+            ),
+            SolveError::BlockedCell { point } => {
+                write!(f, "BlockedCell: ({}, {}) is blocked", point.row, point.col)
+            }
+            SolveError::SameEndpoints { point } => write!(
+                f,
+                "SameEndpoints: start and end are both ({}, {})",
+                point.row, point.col
+            ),
+            SolveError::InvalidPath { index, reason } => {
+                write!(f, "InvalidPath: {} at index {}", reason, index)
+            }
+            SolveError::InvalidWaypoint { index, reason } => {
+                write!(f, "InvalidWaypoint: {} at index {}", reason, index)
+            }
+            SolveError::InvalidFixedEdge { index, reason } => {
+                write!(f, "InvalidFixedEdge: {} at index {}", reason, index)
+            }
+            SolveError::InvalidWall { index, reason } => {
+                write!(f, "InvalidWall: {} at index {}", reason, index)
+            }
+            SolveError::InvalidEncoding { index, reason } => {
+                write!(f, "InvalidEncoding: {} at index {}", reason, index)
+            }
+            SolveError::SerializationFailed(message) => {
+                write!(f, "SerializationFailed: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<serde_wasm_bindgen::Error> for SolveError {
+    fn from(error: serde_wasm_bindgen::Error) -> Self {
+        SolveError::SerializationFailed(error.to_string())
+    }
+}
@@ -0,0 +1,271 @@
+//! Counting Hamiltonian paths with a frontier (plug) dynamic program
+//!
+//! Cells are processed in row-major order while a profile records, for each
+//! edge crossing the boundary between processed and unprocessed cells, which
+//! partial road it belongs to. Partial roads are either pairs (both ends on
+//! the frontier) or singles (one end on the frontier, the other at `start` or
+//! `end`); joining the two singles completes the road. The state count depends
+//! on the width only, so the narrower side is used as the width.
+
+use std::collections::HashMap;
+
+use crate::bigint::BigUint;
+use crate::grid::Grid;
+use crate::Point;
+
+/// Widest grid the counter handles (one 4-bit label per frontier edge)
+pub(crate) const MAX_FRONTIER_WIDTH: i32 = 12;
+
+/// Label of a partial road whose other end is `start` or `end`
+const SINGLE: u64 = 1;
+/// Temporary label for a new pair, renumbered by `normalize`
+const FRESH: u64 = 15;
+
+#[inline]
+fn get(state: u64, i: usize) -> u64 {
+    (state >> (4 * i)) & 0xF
+}
+
+#[inline]
+fn set(state: u64, i: usize, label: u64) -> u64 {
+    (state & !(0xF << (4 * i))) | (label << (4 * i))
+}
+
+/// Renumber pair labels by first appearance so equivalent states coincide
+fn normalize(state: u64, width: usize) -> u64 {
+    let mut map = [0u64; 16];
+    let mut next = SINGLE + 1;
+    let mut out = 0;
+    for i in 0..=width {
+        let label = get(state, i);
+        let label = match label {
+            0 | SINGLE => label,
+            _ => {
+                if map[label as usize] == 0 {
+                    map[label as usize] = next;
+                    next += 1;
+                }
+                map[label as usize]
+            }
+        };
+        out = set(out, i, label);
+    }
+    out
+}
+
+/// Replace every `from` label with `to`
+fn relabel(state: u64, width: usize, from: u64, to: u64) -> u64 {
+    let mut out = state;
+    for i in 0..=width {
+        if get(out, i) == from {
+            out = set(out, i, to);
+        }
+    }
+    out
+}
+
+/// Count Hamiltonian paths from `start` to `end` over the open cells of
+/// `grid`, whose narrower side must be at most `MAX_FRONTIER_WIDTH`
+pub(crate) fn count_paths(start: Point, end: Point, grid: &Grid) -> BigUint {
+    // Sweep along the longer side so the frontier spans the shorter one
+    let transpose = grid.cols > grid.rows;
+    let (rows, width) = if transpose {
+        (grid.cols, grid.rows as usize)
+    } else {
+        (grid.rows, grid.cols as usize)
+    };
+    let open = |row: i32, col: i32| {
+        if row >= rows || col as usize >= width {
+            return false;
+        }
+        if transpose {
+            grid.is_open(col, row)
+        } else {
+            grid.is_open(row, col)
+        }
+    };
+    // Down and right in sweep coordinates swap places when transposed
+    let walled = |row: i32, col: i32, direction: usize| {
+        if transpose {
+            grid.is_walled(col, row, 4 - direction)
+        } else {
+            grid.is_walled(row, col, direction)
+        }
+    };
+    let is_terminal = |row: i32, col: i32| {
+        let p = if transpose {
+            Point { row: col, col: row }
+        } else {
+            Point { row, col }
+        };
+        p == start || p == end
+    };
+
+    // The road can only be completed at the last open cell
+    let last_open = (0..rows)
+        .rev()
+        .flat_map(|row| (0..width as i32).rev().map(move |col| (row, col)))
+        .find(|&(row, col)| open(row, col));
+
+    let mut total = BigUint::zero();
+    let mut states: HashMap<u64, BigUint> = HashMap::from([(0, BigUint::from(1))]);
+
+    for row in 0..rows {
+        for col in 0..width as i32 {
+            let c = col as usize;
+            let can_down = open(row + 1, col) && !walled(row, col, 1);
+            let can_right = open(row, col + 1) && !walled(row, col, 3);
+            let is_last = last_open == Some((row, col));
+            let mut next_states: HashMap<u64, BigUint> = HashMap::new();
+            let mut emit = |state: u64, count: &BigUint| {
+                next_states
+                    .entry(normalize(state, width))
+                    .or_default()
+                    .add_assign(count);
+            };
+
+            for (&state, count) in &states {
+                let left = get(state, c);
+                let up = get(state, c + 1);
+                let base = set(set(state, c, 0), c + 1, 0);
+                // Completing the road is only valid if nothing else is open
+                let completes = is_last && base == 0;
+
+                if !open(row, col) {
+                    if left == 0 && up == 0 {
+                        emit(state, count);
+                    }
+                    continue;
+                }
+
+                if is_terminal(row, col) {
+                    // Endpoints have exactly one road edge
+                    match (left, up) {
+                        (0, 0) => {
+                            if can_down {
+                                emit(set(base, c, SINGLE), count);
+                            }
+                            if can_right {
+                                emit(set(base, c + 1, SINGLE), count);
+                            }
+                        }
+                        (SINGLE, 0) | (0, SINGLE) => {
+                            if completes {
+                                total.add_assign(count);
+                            }
+                        }
+                        (label, 0) | (0, label) => emit(relabel(base, width, label, SINGLE), count),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                match (left, up) {
+                    (0, 0) => {
+                        if can_down && can_right {
+                            emit(set(set(base, c, FRESH), c + 1, FRESH), count);
+                        }
+                    }
+                    (label, 0) | (0, label) => {
+                        if can_down {
+                            emit(set(base, c, label), count);
+                        }
+                        if can_right {
+                            emit(set(base, c + 1, label), count);
+                        }
+                    }
+                    (SINGLE, SINGLE) => {
+                        if completes {
+                            total.add_assign(count);
+                        }
+                    }
+                    // Joining both ends of a pair would close a loop
+                    (a, b) if a == b => {}
+                    (SINGLE, label) | (label, SINGLE) => {
+                        emit(relabel(base, width, label, SINGLE), count)
+                    }
+                    (a, b) => emit(relabel(base, width, b, a), count),
+                }
+            }
+            states = next_states;
+        }
+
+        // Move to the next row: the right edge of the last cell is always empty
+        states = states
+            .into_iter()
+            .map(|(state, count)| (state << 4, count))
+            .collect();
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate::HamiltonianPaths;
+    use crate::Edge;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    #[test]
+    fn test_matches_enumeration() {
+        for rows in 1..=4 {
+            for cols in 1..=5 {
+                let grid = if rows * cols > 6 {
+                    Grid::new(rows, cols)
+                        .with_blocked(&[p(rows / 2, cols / 2)])
+                        .unwrap()
+                } else {
+                    Grid::new(rows, cols)
+                };
+                // A wall next to the corner, once along each side
+                let grid = if rows > 1 && cols > 1 && (rows + cols) % 2 == 0 {
+                    grid.with_walls(&[Edge {
+                        from: p(0, 0),
+                        to: p(0, 1),
+                    }])
+                    .unwrap()
+                } else if rows > 1 && cols > 1 {
+                    grid.with_walls(&[Edge {
+                        from: p(1, 0),
+                        to: p(1, 1),
+                    }])
+                    .unwrap()
+                } else {
+                    grid
+                };
+                let open: Vec<Point> = grid.open_points().collect();
+                for &start in &open {
+                    for &end in &open {
+                        if start == end {
+                            continue;
+                        }
+                        let expected = HamiltonianPaths::new(start, end, &grid).count();
+                        assert_eq!(
+                            count_paths(start, end, &grid),
+                            BigUint::from(expected as u64),
+                            "{}x{} {:?} -> {:?}",
+                            rows,
+                            cols,
+                            start,
+                            end
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjacent_corner_counts() {
+        // OEIS A000532: paths between two adjacent corners of an n x n grid
+        let expected = [1u64, 1, 2, 8, 86, 1770, 88418, 8934966];
+        for (n, &expected) in (1..).zip(expected.iter()).skip(1) {
+            let count = count_paths(p(0, 0), p(n - 1, 0), &Grid::new(n, n));
+            assert_eq!(count, BigUint::from(expected), "{}x{}", n, n);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+//! Grid model with an obstacle mask and walls
+//!
+//! A `Grid` is the full rectangle described by `GridSize` minus a set of
+//! blocked cells (lakes, buildings, map borders) the road has to avoid. Walls
+//! (rivers, fences) cut the connection between two adjacent open cells
+//! without blocking either of them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+use crate::{direction_between, is_in_bounds, Edge, GridSize, InfeasibleReason, Point, DIRECTIONS};
+
+/// Rectangular grid with optional masks of blocked cells and walls
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Grid {
+    pub rows: i32,
+    pub cols: i32,
+    /// Row-major mask of blocked cells; empty means every cell is open
+    #[serde(default)]
+    pub blocked: Vec<bool>,
+    /// Row-major walls of each cell as a bitmask over `DIRECTIONS`; empty
+    /// means no walls. A wall recorded on either side of an edge blocks it.
+    #[serde(default)]
+    pub walls: Vec<u8>,
+}
+
+impl Grid {
+    /// Full rectangle without obstacles
+    pub fn new(rows: i32, cols: i32) -> Self {
+        Self {
+            rows,
+            cols,
+            blocked: Vec::new(),
+            walls: Vec::new(),
+        }
+    }
+
+    /// Same grid with the given cells blocked. Cells outside the grid, or an
+    /// existing mask of the wrong length, are rejected.
+    pub fn with_blocked(mut self, cells: &[Point]) -> Result<Self, SolveError> {
+        let cell_count = self.cell_count();
+        if self.blocked.is_empty() {
+            self.blocked = vec![false; cell_count];
+        } else if self.blocked.len() != cell_count {
+            return Err(SolveError::InvalidObstacleMask {
+                expected: cell_count,
+                actual: self.blocked.len(),
+            });
+        }
+        for &point in cells {
+            if !is_in_bounds(point.row, point.col, &self.size()) {
+                return Err(SolveError::OutOfBounds {
+                    point,
+                    grid_size: self.size(),
+                });
+            }
+            let i = self.index(point.row, point.col);
+            self.blocked[i] = true;
+        }
+        Ok(self)
+    }
+
+    /// Same grid with walls across the given edges of adjacent cells. Edges
+    /// with a cell outside the grid or between cells that are not adjacent,
+    /// or an existing mask of the wrong length, are rejected.
+    pub fn with_walls(mut self, edges: &[Edge]) -> Result<Self, SolveError> {
+        let cell_count = self.cell_count();
+        if self.walls.is_empty() {
+            self.walls = vec![0; cell_count];
+        } else if self.walls.len() != cell_count {
+            return Err(SolveError::InvalidWallMask {
+                expected: cell_count,
+                actual: self.walls.len(),
+            });
+        }
+        for (index, edge) in edges.iter().enumerate() {
+            for point in [edge.from, edge.to] {
+                if !is_in_bounds(point.row, point.col, &self.size()) {
+                    return Err(SolveError::OutOfBounds {
+                        point,
+                        grid_size: self.size(),
+                    });
+                }
+            }
+            let Some(direction) = direction_between(edge.from, edge.to) else {
+                return Err(SolveError::InvalidWall {
+                    index,
+                    reason: "cells are not adjacent",
+                });
+            };
+            let (from, to) = (
+                self.index(edge.from.row, edge.from.col),
+                self.index(edge.to.row, edge.to.col),
+            );
+            self.walls[from] |= 1 << direction;
+            // Directions come in opposite pairs: up/down, left/right
+            self.walls[to] |= 1 << (direction ^ 1);
+        }
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn size(&self) -> GridSize {
+        GridSize {
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    #[inline]
+    fn index(&self, row: i32, col: i32) -> usize {
+        (row * self.cols + col) as usize
+    }
+
+    #[inline]
+    fn cell_count(&self) -> usize {
+        self.rows.max(0) as usize * self.cols.max(0) as usize
+    }
+
+    /// Check if a cell is blocked; cells outside the grid or past the end of
+    /// the mask are not
+    #[inline]
+    pub fn is_blocked(&self, row: i32, col: i32) -> bool {
+        !self.blocked.is_empty()
+            && is_in_bounds(row, col, &self.size())
+            && self.blocked.get(self.index(row, col)) == Some(&true)
+    }
+
+    /// Check if a cell is in bounds and not blocked
+    #[inline]
+    pub fn is_open(&self, row: i32, col: i32) -> bool {
+        is_in_bounds(row, col, &self.size()) && !self.is_blocked(row, col)
+    }
+
+    /// Check if any cell is blocked
+    pub fn has_obstacles(&self) -> bool {
+        self.blocked.iter().any(|&b| b)
+    }
+
+    /// Check if any edge is walled
+    pub fn has_walls(&self) -> bool {
+        self.walls.iter().any(|&w| w != 0)
+    }
+
+    /// Neither blocked cells nor walls, so the exact rectangle results apply
+    pub fn is_full_rectangle(&self) -> bool {
+        !self.has_obstacles() && !self.has_walls()
+    }
+
+    /// Check if a wall separates an in-bounds cell from its neighbor in
+    /// `DIRECTIONS[direction]`, which must also be in bounds
+    #[inline]
+    pub fn is_walled(&self, row: i32, col: i32, direction: usize) -> bool {
+        is_walled(&self.walls, row, col, direction, &self.size())
+    }
+
+    /// Check if the road can step from an in-bounds cell to its neighbor in
+    /// `DIRECTIONS[direction]`: the neighbor is open and no wall is between
+    #[inline]
+    pub fn can_step(&self, row: i32, col: i32, direction: usize) -> bool {
+        let (dr, dc, _) = DIRECTIONS[direction];
+        self.is_open(row + dr, col + dc) && !self.is_walled(row, col, direction)
+    }
+
+    /// Number of cells the road has to visit
+    pub fn open_cells(&self) -> usize {
+        self.open_points().count()
+    }
+
+    /// Iterate over open cells in row-major order
+    pub(crate) fn open_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.rows)
+            .flat_map(move |row| (0..self.cols).map(move |col| Point { row, col }))
+            .filter(move |p| !self.is_blocked(p.row, p.col))
+    }
+
+    /// Count open neighbors of a cell that are not walled off
+    pub(crate) fn open_degree(&self, row: i32, col: i32) -> usize {
+        (0..DIRECTIONS.len())
+            .filter(|&direction| self.can_step(row, col, direction))
+            .count()
+    }
+}
+
+/// Wall check on a raw `Grid::walls` mask, for searches that keep their own
+/// copy. Both cells must be in bounds; an empty mask has no walls.
+#[inline]
+pub(crate) fn is_walled(
+    walls: &[u8],
+    row: i32,
+    col: i32,
+    direction: usize,
+    grid_size: &GridSize,
+) -> bool {
+    if walls.is_empty() {
+        return false;
+    }
+    let (dr, dc, _) = DIRECTIONS[direction];
+    let here = (row * grid_size.cols + col) as usize;
+    let there = ((row + dr) * grid_size.cols + col + dc) as usize;
+    // Directions come in opposite pairs: up/down, left/right
+    walls[here] & (1 << direction) != 0 || walls[there] & (1 << (direction ^ 1)) != 0
+}
+
+impl From<GridSize> for Grid {
+    fn from(grid_size: GridSize) -> Self {
+        Grid::new(grid_size.rows, grid_size.cols)
+    }
+}
+
+/// Necessary conditions for a Hamiltonian path on a grid with obstacles.
+///
+/// Unlike full rectangles there is no exact characterization, so `None` only
+/// means no obstruction was detected. Both endpoints must be open.
+pub(crate) fn obstacle_obstruction(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Option<InfeasibleReason> {
+    ObstacleFacts::new(grid).obstruction(start, end)
+}
+
+/// The endpoint-independent facts behind `obstacle_obstruction`, gathered in
+/// one pass so many endpoint pairs can be checked in constant time each
+pub(crate) struct ObstacleFacts {
+    /// Open cells of even color minus open cells of odd color
+    color_balance: i64,
+    /// Some open cell has no open neighbor
+    has_isolated_cell: bool,
+    /// Open cells with exactly one open neighbor (at most three are kept)
+    leaves: Vec<Point>,
+    connected: bool,
+}
+
+impl ObstacleFacts {
+    pub(crate) fn new(grid: &Grid) -> Self {
+        let mut color_balance = 0i64;
+        let mut has_isolated_cell = false;
+        let mut leaves = Vec::new();
+        for p in grid.open_points() {
+            color_balance += if (p.row + p.col) % 2 == 0 { 1 } else { -1 };
+            match grid.open_degree(p.row, p.col) {
+                0 => has_isolated_cell = true,
+                1 if leaves.len() < 3 => leaves.push(p),
+                _ => {}
+            }
+        }
+        Self {
+            color_balance,
+            has_isolated_cell,
+            leaves,
+            connected: is_open_region_connected(grid),
+        }
+    }
+
+    pub(crate) fn obstruction(&self, start: Point, end: Point) -> Option<InfeasibleReason> {
+        if start == end {
+            return Some(InfeasibleReason::SameEndpoints);
+        }
+
+        // A path alternates colors, so the open cells must split evenly between
+        // them, with the endpoints on the majority color when the count is odd
+        let color = |p: Point| (p.row + p.col) % 2;
+        match self.color_balance {
+            0 if color(start) == color(end) => return Some(InfeasibleReason::Parity),
+            0 => {}
+            1 if color(start) != 0 || color(end) != 0 => {
+                return Some(InfeasibleReason::ColorImbalance)
+            }
+            -1 if color(start) != 1 || color(end) != 1 => {
+                return Some(InfeasibleReason::ColorImbalance)
+            }
+            1 | -1 => {}
+            _ => return Some(InfeasibleReason::ColorImbalance),
+        }
+
+        // Every cell other than the endpoints needs two open neighbors
+        let dead_end =
+            self.has_isolated_cell || self.leaves.iter().any(|&p| p != start && p != end);
+        if dead_end {
+            return Some(InfeasibleReason::DeadEnd);
+        }
+
+        if !self.connected {
+            return Some(InfeasibleReason::Disconnected);
+        }
+
+        None
+    }
+}
+
+/// Check that all open cells form a single connected region
+pub(crate) fn is_open_region_connected(grid: &Grid) -> bool {
+    let Some(first) = grid.open_points().next() else {
+        return true;
+    };
+
+    let mut seen = vec![false; grid.cell_count()];
+    let mut stack = vec![first];
+    seen[grid.index(first.row, first.col)] = true;
+    let mut reached = 0usize;
+
+    while let Some(p) = stack.pop() {
+        reached += 1;
+        for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
+            let (nr, nc) = (p.row + dr, p.col + dc);
+            if grid.can_step(p.row, p.col, direction) && !seen[grid.index(nr, nc)] {
+                seen[grid.index(nr, nc)] = true;
+                stack.push(Point { row: nr, col: nc });
+            }
+        }
+    }
+
+    reached == grid.open_cells()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    #[test]
+    fn test_open_cells() {
+        let grid = Grid::new(3, 4).with_blocked(&[p(1, 1), p(2, 3)]).unwrap();
+        assert_eq!(grid.open_cells(), 10);
+        assert!(grid.has_obstacles());
+        assert!(!grid.is_open(1, 1));
+        assert!(!grid.is_open(-1, 0));
+        assert!(grid.is_open(0, 0));
+        assert_eq!(grid.open_degree(0, 1), 2);
+        assert!(!Grid::new(3, 4).has_obstacles());
+    }
+
+    #[test]
+    fn test_out_of_range_cells_do_not_panic() {
+        let grid_size = GridSize { rows: 3, cols: 4 };
+        assert_eq!(
+            Grid::new(3, 4).with_blocked(&[p(1, -1)]),
+            Err(SolveError::OutOfBounds {
+                point: p(1, -1),
+                grid_size
+            })
+        );
+        assert!(Grid::new(3, 4).with_blocked(&[p(3, 0)]).is_err());
+
+        // A mask of the wrong length reads as open past its end
+        let mut short = Grid::new(3, 4);
+        short.blocked = vec![true, false];
+        assert!(short.is_blocked(0, 0));
+        assert!(!short.is_blocked(2, 3));
+        assert!(short.is_open(2, 3));
+        assert!(!short.is_blocked(-1, 0));
+        assert!(matches!(
+            short.with_blocked(&[p(0, 1)]),
+            Err(SolveError::InvalidObstacleMask { .. })
+        ));
+    }
+
+    #[test]
+    fn test_obstacle_obstructions() {
+        // Blocking one cell of a 3x3 leaves four cells of each color
+        let grid = Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap();
+        assert_eq!(
+            obstacle_obstruction(p(0, 0), p(0, 2), &grid),
+            Some(InfeasibleReason::Parity)
+        );
+        assert_eq!(obstacle_obstruction(p(0, 0), p(0, 1), &grid), None);
+
+        // Two blocked cells of the same color unbalance the colors
+        let grid = Grid::new(4, 4).with_blocked(&[p(0, 0), p(1, 1)]).unwrap();
+        assert_eq!(
+            obstacle_obstruction(p(0, 1), p(0, 3), &grid),
+            Some(InfeasibleReason::ColorImbalance)
+        );
+
+        // A blocked row across the middle splits the grid
+        let grid = Grid::new(5, 2).with_blocked(&[p(2, 0), p(2, 1)]).unwrap();
+        assert_eq!(
+            obstacle_obstruction(p(0, 0), p(4, 1), &grid),
+            Some(InfeasibleReason::Disconnected)
+        );
+
+        // (0, 0) is only reachable from (0, 1) and must be an endpoint
+        let grid = Grid::new(3, 4).with_blocked(&[p(1, 0), p(2, 0)]).unwrap();
+        assert_eq!(
+            obstacle_obstruction(p(0, 1), p(2, 2), &grid),
+            Some(InfeasibleReason::DeadEnd)
+        );
+        assert_eq!(obstacle_obstruction(p(0, 0), p(2, 1), &grid), None);
+    }
+
+    #[test]
+    fn test_grid_serde_defaults_to_no_obstacles() {
+        let grid: Grid = serde_json::from_str(r#"{"rows":2,"cols":3}"#).unwrap();
+        assert_eq!(grid, Grid::new(2, 3));
+        assert_eq!(grid.open_cells(), 6);
+    }
+
+    #[test]
+    fn test_walls() {
+        // A fence between (0, 1) and (1, 1)
+        let grid = Grid::new(2, 3)
+            .with_walls(&[Edge {
+                from: p(1, 1),
+                to: p(0, 1),
+            }])
+            .unwrap();
+        assert!(grid.has_walls());
+        assert!(!grid.is_full_rectangle());
+        assert_eq!(grid.open_cells(), 6);
+        assert!(grid.is_walled(0, 1, 1));
+        assert!(grid.is_walled(1, 1, 0));
+        assert!(!grid.can_step(0, 1, 1));
+        assert!(grid.can_step(0, 1, 2));
+        assert_eq!(grid.open_degree(0, 1), 2);
+        assert_eq!(grid.open_degree(1, 1), 2);
+
+        // A wall recorded on one side only still blocks both directions
+        let mut one_sided = Grid::new(2, 3);
+        one_sided.walls = vec![0, 0b10, 0, 0, 0, 0];
+        assert!(one_sided.is_walled(1, 1, 0));
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+
+        // An edge leaving the grid is rejected instead of indexing past it
+        let off_grid = Edge {
+            from: p(1, 2),
+            to: p(1, 3),
+        };
+        assert!(matches!(
+            Grid::new(2, 3).with_walls(&[off_grid]),
+            Err(SolveError::OutOfBounds { point, .. }) if point == p(1, 3)
+        ));
+
+        // So is one between cells that do not touch
+        let diagonal = Edge {
+            from: p(0, 0),
+            to: p(1, 1),
+        };
+        let fence = Edge {
+            from: p(0, 0),
+            to: p(0, 1),
+        };
+        assert_eq!(
+            Grid::new(2, 3).with_walls(&[fence, diagonal]),
+            Err(SolveError::InvalidWall {
+                index: 1,
+                reason: "cells are not adjacent"
+            })
+        );
+
+        // A river down the middle splits the grid into two 2x2 blocks
+        let split = Grid::new(2, 4)
+            .with_walls(&[
+                Edge {
+                    from: p(0, 1),
+                    to: p(0, 2),
+                },
+                Edge {
+                    from: p(1, 1),
+                    to: p(1, 2),
+                },
+            ])
+            .unwrap();
+        assert!(!is_open_region_connected(&split));
+        assert_eq!(
+            obstacle_obstruction(p(0, 0), p(1, 0), &split),
+            Some(InfeasibleReason::Disconnected)
+        );
+    }
+}
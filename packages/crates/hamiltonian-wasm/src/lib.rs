@@ -6,6 +6,45 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+mod bigint;
+mod clock;
+mod connections;
+mod constructive;
+mod cycle;
+mod encoding;
+mod enumerate;
+mod error;
+mod frontier;
+mod grid;
+mod rng;
+mod sampler;
+mod solver;
+mod svg;
+#[cfg(test)]
+mod test_util;
+mod text;
+mod tiles;
+mod turns;
+mod verify;
+
+use rng::Rng;
+use sampler::FixedEnds;
+use solver::Search;
+
+pub use bigint::BigUint;
+pub use clock::{Clock, SystemClock};
+pub use connections::Connections;
+pub use encoding::{decode_path, encode_path, PathEncoding};
+pub use enumerate::HamiltonianPaths;
+pub use error::SolveError;
+pub use grid::Grid;
+pub use solver::{PartialPath, SearchStats, SolverStatus, Termination};
+pub use svg::{render_svg, SvgStyle};
+pub use text::RoadGrid;
+pub use tiles::{Axis, Corner, Direction, RoadTile, TileCell};
+pub use turns::{count_turns, TurnObjective};
+pub use verify::{verify_hamiltonian_path, PathConstraints, Violation, ViolationKind};
+
 // パニック時のスタックトレースをより分かりやすくする
 fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
@@ -22,23 +61,108 @@ pub struct Point {
     pub col: i32,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Road segment between two adjacent cells, in either direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: Point,
+    pub to: Point,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GridSize {
     pub rows: i32,
     pub cols: i32,
 }
 
+/// Why a Hamiltonian path between two cells cannot exist
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InfeasibleReason {
+    /// Start and end are the same cell
+    SameEndpoints,
+    /// Even cell count but both endpoints have the same checkerboard color
+    Parity,
+    /// Odd cell count but an endpoint is not on the majority color
+    ColorImbalance,
+    /// 1xN grid where the endpoints are not the two ends of the line
+    SingleLine,
+    /// 2xN grid where the endpoints form an inner rung of the ladder
+    LadderRung,
+    /// 3xN grid (N even) in one of the forbidden endpoint configurations
+    ThreeWideTrap,
+    /// A non-endpoint open cell has fewer than two open neighbors
+    DeadEnd,
+    /// The open cells do not form a single connected region
+    Disconnected,
+    /// A closed loop needs an even number of cells
+    OddCellCount,
+    /// An exhaustive search completed without finding a path
+    Exhausted,
+    /// Waypoint `index` cannot be reached from the start without passing
+    /// the end or a later waypoint
+    UnreachableWaypoint { index: usize },
+}
+
+/// Tri-state answer to "does a Hamiltonian path exist?"
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "verdict", content = "reason", rename_all = "snake_case")]
+pub enum Feasibility {
+    Possible,
+    Impossible(InfeasibleReason),
+    /// The search gave up before deciding
+    Unknown,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathResult {
     pub found: bool,
     pub path: Vec<Point>,
     pub iterations: u32,
+    pub verdict: Feasibility,
+    /// How the search ended; absent when no search ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub termination: Option<Termination>,
+    /// Work done by the search; absent when no search ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<SearchStats>,
+    /// Best-effort road when a search ran without finding a path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial: Option<PartialPath>,
+    /// Bends in `path` after turn optimization; absent without an objective
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turns: Option<u32>,
+}
+
+/// Budgets, constraints and tie-breaking for the backtracking search
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Cells the search may enter after the start cell
+    pub max_iterations: u32,
+    /// Randomize neighbor order: ties and near-ties in the heuristic ranking
+    /// are reordered per seed
+    pub seed: Option<u64>,
+    /// Wall-clock budget in milliseconds
+    pub time_limit_ms: Option<f64>,
+    /// Report progress every this many iterations
+    pub progress_interval: Option<u32>,
+    /// Cells the road must pass through, in this order
+    pub waypoints: Vec<Point>,
+    /// Road segments every solution must use
+    pub fixed_edges: Vec<Edge>,
+    /// Reshape a found road towards this many bends
+    pub turn_objective: Option<TurnObjective>,
+    /// Moves the turn optimization tries
+    pub turn_steps: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CellData {
-    pub connections: Vec<String>,
+    /// Connected directions (1 up, 2 down, 4 left, 8 right)
+    pub mask: Connections,
     pub path_index: usize,
+    /// The connections that came from fixed edges rather than the generator
+    #[serde(default)]
+    pub fixed: Connections,
 }
 
 // ============================================================================
@@ -52,25 +176,62 @@ const DIRECTIONS: [(i32, i32, &str); 4] = [
     (0, 1, "right"),
 ];
 
-// Maximum supported grid size: 20x40 = 800 cells
-// We use 13 x u64 = 832 bits to cover this
-const BITSET_SIZE: usize = 13;
+/// Upper bound (exclusive) of the random priority offset a seed adds; well
+/// below the 100 that separates two Warnsdorff neighbor counts
+const SEED_JITTER: usize = 30;
+
+/// Search moves between two reads of the clock
+const CLOCK_CHECK_INTERVAL: u32 = 256;
+
+/// Search budget of the `find_hamiltonian_path_with_options` export when none
+/// is given
+const DEFAULT_MAX_ITERATIONS: u32 = 500_000;
+
+/// Turn optimization moves when the caller does not give a number
+const DEFAULT_TURN_STEPS: u32 = 1_000;
+
+/// Largest grid accepted by the solvers (2048x2048 cells)
+const MAX_GRID_CELLS: usize = 1 << 22;
 
 /// Compact bitset for tracking visited cells
-/// Much faster than Vec<Vec<bool>> due to better cache locality
-#[derive(Clone, Copy)]
+/// Much faster than Vec<Vec<bool>> due to better cache locality.
+/// Sized to the grid at construction, one bit per cell.
+#[derive(Clone, Serialize, Deserialize)]
 struct VisitedBitset {
-    bits: [u64; BITSET_SIZE],
+    bits: Vec<u64>,
     cols: usize,
 }
 
 impl VisitedBitset {
     #[inline]
-    fn new(cols: usize) -> Self {
+    fn new(grid_size: &GridSize) -> Self {
+        let cells = grid_size.rows as usize * grid_size.cols as usize;
         Self {
-            bits: [0; BITSET_SIZE],
-            cols,
+            bits: vec![0; cells.div_ceil(64)],
+            cols: grid_size.cols as usize,
+        }
+    }
+
+    /// Bitset with the blocked cells of `grid` already set, so a search
+    /// treats them as visited and never enters them
+    fn from_blocked(grid: &Grid) -> Self {
+        let mut bits = Self::new(&grid.size());
+        if grid.has_obstacles() {
+            for row in 0..grid.rows {
+                for col in 0..grid.cols {
+                    if grid.is_blocked(row, col) {
+                        bits.set(row, col);
+                    }
+                }
+            }
         }
+        bits
+    }
+
+    /// Clear every bit, keeping the allocation for reuse
+    #[inline]
+    fn reset(&mut self) {
+        self.bits.fill(0);
     }
 
     #[inline]
@@ -104,7 +265,7 @@ impl VisitedBitset {
 
 /// Check if position is within grid bounds
 #[inline]
-fn is_in_bounds(row: i32, col: i32, grid_size: &GridSize) -> bool {
+pub(crate) fn is_in_bounds(row: i32, col: i32, grid_size: &GridSize) -> bool {
     row >= 0 && row < grid_size.rows && col >= 0 && col < grid_size.cols
 }
 
@@ -114,20 +275,47 @@ fn get_cell_parity(row: i32, col: i32) -> i32 {
     (row + col) % 2
 }
 
+/// Index into `DIRECTIONS` of the step from `from` to the adjacent `to`
+#[inline]
+fn direction_between(from: Point, to: Point) -> Option<usize> {
+    DIRECTIONS
+        .iter()
+        .position(|&(dr, dc, _)| from.row + dr == to.row && from.col + dc == to.col)
+}
+
+/// Fixed edges of every cell as a bitmask over `DIRECTIONS`, row-major.
+/// Edges must join adjacent cells of the grid.
+fn fixed_edge_masks(edges: &[Edge], grid_size: &GridSize) -> Vec<u8> {
+    let mut masks = vec![0u8; grid_size.rows as usize * grid_size.cols as usize];
+    for edge in edges {
+        for (a, b) in [(edge.from, edge.to), (edge.to, edge.from)] {
+            if let Some(direction) = direction_between(a, b) {
+                masks[(a.row * grid_size.cols + a.col) as usize] |= 1 << direction;
+            }
+        }
+    }
+    masks
+}
+
 /// Get unvisited neighboring cells (bitset version)
+/// Neighbors behind a wall (see `Grid::walls`) are left out.
 fn get_unvisited_neighbors(
     row: i32,
     col: i32,
     grid_size: &GridSize,
+    walls: &[u8],
     visited: &VisitedBitset,
 ) -> Vec<(i32, i32, &'static str)> {
     let mut neighbors = Vec::with_capacity(4);
 
-    for &(dr, dc, dir) in &DIRECTIONS {
+    for (direction, &(dr, dc, dir)) in DIRECTIONS.iter().enumerate() {
         let nr = row + dr;
         let nc = col + dc;
 
-        if is_in_bounds(nr, nc, grid_size) && !visited.get(nr, nc) {
+        if is_in_bounds(nr, nc, grid_size)
+            && !visited.get(nr, nc)
+            && !grid::is_walled(walls, row, col, direction, grid_size)
+        {
             neighbors.push((nr, nc, dir));
         }
     }
@@ -137,12 +325,21 @@ fn get_unvisited_neighbors(
 
 /// Count unvisited neighbors (optimized - no allocation, bitset version)
 #[inline]
-fn count_unvisited_neighbors(row: i32, col: i32, grid_size: &GridSize, visited: &VisitedBitset) -> u8 {
+fn count_unvisited_neighbors(
+    row: i32,
+    col: i32,
+    grid_size: &GridSize,
+    walls: &[u8],
+    visited: &VisitedBitset,
+) -> u8 {
     let mut count = 0u8;
-    for &(dr, dc, _) in &DIRECTIONS {
+    for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
         let nr = row + dr;
         let nc = col + dc;
-        if is_in_bounds(nr, nc, grid_size) && !visited.get(nr, nc) {
+        if is_in_bounds(nr, nc, grid_size)
+            && !visited.get(nr, nc)
+            && !grid::is_walled(walls, row, col, direction, grid_size)
+        {
             count += 1;
         }
     }
@@ -179,6 +376,7 @@ fn calculate_neighbor_priority(
     col: i32,
     target: &Point,
     grid_size: &GridSize,
+    walls: &[u8],
     visited: &VisitedBitset,
     unvisited_count: usize,
 ) -> u32 {
@@ -187,7 +385,7 @@ fn calculate_neighbor_priority(
         return u32::MAX;
     }
 
-    let neighbor_count = count_unvisited_neighbors(row, col, grid_size, visited) as u32;
+    let neighbor_count = count_unvisited_neighbors(row, col, grid_size, walls, visited) as u32;
 
     // Base score: Warnsdorff's heuristic (0-4 neighbors, scaled)
     // Cells with fewer options should be visited first
@@ -228,21 +426,42 @@ fn calculate_neighbor_priority(
 }
 
 /// Sort neighbors using enhanced Warnsdorff's heuristic
-/// Combines multiple strategies for better path finding
+/// Combines multiple strategies for better path finding.
+/// With an `rng`, every priority gets a random jitter below `SEED_JITTER`, so
+/// neighbors ranked that close can swap places, and neighbors are shuffled
+/// first so the stable sort breaks the remaining ties randomly instead of by
+/// direction order.
 fn sort_by_warnsdorff(
     neighbors: &mut Vec<(i32, i32, &'static str)>,
     target: &Point,
     grid_size: &GridSize,
+    walls: &[u8],
     visited: &VisitedBitset,
     unvisited_count: usize,
+    mut rng: Option<&mut Rng>,
 ) {
+    if let Some(rng) = rng.as_deref_mut() {
+        rng.shuffle(neighbors);
+    }
+
     // Calculate priority scores for all neighbors
     let mut neighbors_with_priority: Vec<((i32, i32, &'static str), u32)> = neighbors
         .iter()
         .map(|&n| {
-            let priority =
-                calculate_neighbor_priority(n.0, n.1, target, grid_size, visited, unvisited_count);
-            (n, priority)
+            let priority = calculate_neighbor_priority(
+                n.0,
+                n.1,
+                target,
+                grid_size,
+                walls,
+                visited,
+                unvisited_count,
+            );
+            // The target keeps `u32::MAX` and stays last
+            let jitter = rng
+                .as_deref_mut()
+                .map_or(0, |rng| rng.below(SEED_JITTER) as u32);
+            (n, priority.saturating_add(jitter))
         })
         .collect();
 
@@ -258,10 +477,14 @@ fn sort_by_warnsdorff(
 /// Check if remaining unvisited cells are connected (optimized version with bitset)
 /// Uses a more efficient approach: instead of collecting all unvisited cells first,
 /// we do a single DFS and count reachable cells.
+/// `dfs_visited` is scratch space reused across calls to avoid reallocating.
+/// Walled edges do not connect cells.
 fn is_remaining_connected(
     grid_size: &GridSize,
+    walls: &[u8],
     visited: &VisitedBitset,
     unvisited_count: usize,
+    dfs_visited: &mut VisitedBitset,
 ) -> bool {
     if unvisited_count <= 1 {
         return true;
@@ -285,7 +508,7 @@ fn is_remaining_connected(
 
     // DFS using stack (faster than BFS for connectivity check)
     // Use bitset for DFS visited tracking (much faster than Vec<bool>)
-    let mut dfs_visited = VisitedBitset::new(grid_size.cols as usize);
+    dfs_visited.reset();
     let mut stack = Vec::with_capacity(unvisited_count.min(64));
     let mut reachable_count = 0usize;
 
@@ -300,11 +523,15 @@ fn is_remaining_connected(
             return true;
         }
 
-        for &(dr, dc, _) in &DIRECTIONS {
+        for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
             let nr = r + dr;
             let nc = c + dc;
 
-            if is_in_bounds(nr, nc, grid_size) && !visited.get(nr, nc) && !dfs_visited.get(nr, nc) {
+            if is_in_bounds(nr, nc, grid_size)
+                && !visited.get(nr, nc)
+                && !dfs_visited.get(nr, nc)
+                && !grid::is_walled(walls, r, c, direction, grid_size)
+            {
                 dfs_visited.set(nr, nc);
                 stack.push((nr, nc));
             }
@@ -321,10 +548,11 @@ fn is_likely_articulation_point(
     row: i32,
     col: i32,
     grid_size: &GridSize,
+    walls: &[u8],
     visited: &VisitedBitset,
 ) -> bool {
     // Count unvisited neighbors
-    let neighbor_count = count_unvisited_neighbors(row, col, grid_size, visited);
+    let neighbor_count = count_unvisited_neighbors(row, col, grid_size, walls, visited);
 
     // If cell has 0 or 1 unvisited neighbor, it's not an articulation point
     // (it's either isolated or a leaf)
@@ -344,218 +572,911 @@ fn is_likely_articulation_point(
     neighbor_count >= 2
 }
 
+/// Reject empty grids, grids with more than `max_cells` cells and obstacle or
+/// wall masks that do not match the grid
+fn validate_grid(grid: &Grid, max_cells: usize) -> Result<(), SolveError> {
+    if grid.rows < 1 || grid.cols < 1 {
+        return Err(SolveError::InvalidGridSize {
+            grid_size: grid.size(),
+        });
+    }
+    let cells = grid.rows as usize * grid.cols as usize;
+    if cells > max_cells {
+        return Err(SolveError::GridTooLarge { cells, max_cells });
+    }
+    if !grid.blocked.is_empty() && grid.blocked.len() != cells {
+        return Err(SolveError::InvalidObstacleMask {
+            expected: cells,
+            actual: grid.blocked.len(),
+        });
+    }
+    if !grid.walls.is_empty() && grid.walls.len() != cells {
+        return Err(SolveError::InvalidWallMask {
+            expected: cells,
+            actual: grid.walls.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Reject points outside the grid or on blocked cells
+fn validate_point(point: Point, grid: &Grid) -> Result<(), SolveError> {
+    if !is_in_bounds(point.row, point.col, &grid.size()) {
+        return Err(SolveError::OutOfBounds {
+            point,
+            grid_size: grid.size(),
+        });
+    }
+    if grid.is_blocked(point.row, point.col) {
+        return Err(SolveError::BlockedCell { point });
+    }
+    Ok(())
+}
+
+/// Validate a start/end request against a grid of at most `max_cells` cells
+fn validate_endpoints(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+    max_cells: usize,
+) -> Result<(), SolveError> {
+    validate_grid(grid, max_cells)?;
+    validate_point(start, grid)?;
+    validate_point(end, grid)?;
+    if start == end {
+        return Err(SolveError::SameEndpoints { point: start });
+    }
+    Ok(())
+}
+
+/// Check that `path` is a simple path of adjacent open cells of `grid`
+fn validate_path(path: &[Point], grid: &Grid) -> Result<(), SolveError> {
+    validate_grid(grid, MAX_GRID_CELLS)?;
+
+    let mut seen = vec![false; (grid.rows * grid.cols) as usize];
+    for (i, point) in path.iter().enumerate() {
+        validate_point(*point, grid)?;
+        let cell = (point.row * grid.cols + point.col) as usize;
+        if std::mem::replace(&mut seen[cell], true) {
+            return Err(SolveError::InvalidPath {
+                index: i,
+                reason: "cell visited twice",
+            });
+        }
+        if i > 0 {
+            let Some(direction) = direction_between(path[i - 1], *point) else {
+                return Err(SolveError::InvalidPath {
+                    index: i,
+                    reason: "step to a non-adjacent cell",
+                });
+            };
+            if grid.is_walled(path[i - 1].row, path[i - 1].col, direction) {
+                return Err(SolveError::InvalidPath {
+                    index: i,
+                    reason: "step crosses a wall",
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reject waypoints that are off the grid, blocked, repeated or endpoints
+fn validate_waypoints(
+    waypoints: &[Point],
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Result<(), SolveError> {
+    for (index, &point) in waypoints.iter().enumerate() {
+        validate_point(point, grid)?;
+        let reason = if point == start || point == end {
+            "waypoint is an endpoint"
+        } else if waypoints[..index].contains(&point) {
+            "waypoint listed twice"
+        } else {
+            continue;
+        };
+        return Err(SolveError::InvalidWaypoint { index, reason });
+    }
+    Ok(())
+}
+
+/// Reject fixed edges that are malformed or cannot all lie on one path: a
+/// cell takes at most two of them (then both its connections are decided),
+/// an endpoint at most one, and together they must not close a loop
+fn validate_fixed_edges(
+    edges: &[Edge],
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Result<(), SolveError> {
+    let cols = grid.cols;
+    let mut degree = vec![0u8; grid.rows as usize * cols as usize];
+    // Union-find over cells to spot loops
+    let mut parent: Vec<usize> = (0..degree.len()).collect();
+    fn root(parent: &mut [usize], mut cell: usize) -> usize {
+        while parent[cell] != cell {
+            parent[cell] = parent[parent[cell]];
+            cell = parent[cell];
+        }
+        cell
+    }
+
+    for (index, edge) in edges.iter().enumerate() {
+        validate_point(edge.from, grid)?;
+        validate_point(edge.to, grid)?;
+        let invalid = |reason| Err(SolveError::InvalidFixedEdge { index, reason });
+        let Some(direction) = direction_between(edge.from, edge.to) else {
+            return invalid("cells are not adjacent");
+        };
+        if grid.is_walled(edge.from.row, edge.from.col, direction) {
+            return invalid("edge crosses a wall");
+        }
+        let same = |other: &Edge| {
+            (other.from, other.to) == (edge.from, edge.to)
+                || (other.from, other.to) == (edge.to, edge.from)
+        };
+        if edges[..index].iter().any(same) {
+            return invalid("edge listed twice");
+        }
+        for point in [edge.from, edge.to] {
+            let cell = &mut degree[(point.row * cols + point.col) as usize];
+            *cell += 1;
+            if *cell > 2 {
+                return invalid("cell has more than two fixed edges");
+            }
+            if *cell > 1 && (point == start || point == end) {
+                return invalid("endpoint has more than one fixed edge");
+            }
+        }
+        let a = root(&mut parent, (edge.from.row * cols + edge.from.col) as usize);
+        let b = root(&mut parent, (edge.to.row * cols + edge.to.col) as usize);
+        if a == b {
+            return invalid("fixed edges close a loop");
+        }
+        parent[a] = b;
+    }
+    Ok(())
+}
+
+/// Whether a Hamiltonian path, given by the `position` of each open cell on
+/// it, visits `waypoints` in order and uses every fixed edge
+fn satisfies_constraints(
+    position: impl Fn(Point) -> usize,
+    waypoints: &[Point],
+    fixed_edges: &[Edge],
+) -> bool {
+    let mut last = 0;
+    for &waypoint in waypoints {
+        let i = position(waypoint);
+        if i < last {
+            return false;
+        }
+        last = i;
+    }
+    fixed_edges
+        .iter()
+        .all(|edge| position(edge.from).abs_diff(position(edge.to)) == 1)
+}
+
 /// Main Hamiltonian path finding algorithm
+/// Visits every open cell of `grid` exactly once.
 fn find_hamiltonian_path_internal(
     start: Point,
     end: Point,
-    grid_size: GridSize,
+    grid: &Grid,
     max_iterations: u32,
-) -> PathResult {
-    // Validation: same start and end
-    if start == end {
-        return PathResult {
+) -> Result<PathResult, SolveError> {
+    let options = SearchOptions {
+        max_iterations,
+        ..SearchOptions::default()
+    };
+    find_hamiltonian_path_with_options(
+        start,
+        end,
+        grid,
+        &options,
+        &mut SystemClock::new(),
+        &mut |_| {},
+    )
+}
+
+/// Hamiltonian path search with randomization and a wall-clock budget.
+///
+/// A `seed` jitters the neighbor priorities and shuffles their order, so
+/// ties and near-ties are broken differently per seed while the same seed
+/// always yields the same road.
+/// Waypoints are visited in the given order; when one is cut off from the
+/// start by the end or a later waypoint, the verdict names it, and a search
+/// that fails on them names the last one it pruned on in `pruned_waypoint`.
+/// Fixed edges are part of every road.
+/// With a turn objective a found road is then reshaped towards it, keeping
+/// the endpoints, waypoints and fixed edges; the reshaping also stops at the
+/// deadline.
+/// With a time limit the search stops once `clock` passes the deadline; the
+/// clock is read every `CLOCK_CHECK_INTERVAL` moves. With a progress
+/// interval, `on_progress` receives the running stats that often.
+fn find_hamiltonian_path_with_options(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+    options: &SearchOptions,
+    clock: &mut dyn Clock,
+    on_progress: &mut dyn FnMut(&SearchStats),
+) -> Result<PathResult, SolveError> {
+    validate_endpoints(start, end, grid, MAX_GRID_CELLS)?;
+    validate_waypoints(&options.waypoints, start, end, grid)?;
+    validate_fixed_edges(&options.fixed_edges, start, end, grid)?;
+
+    // Early exit: same endpoints, parity and the small-grid exceptions are
+    // decided exactly without searching
+    if let Feasibility::Impossible(reason) = check_feasibility_internal(start, end, grid)? {
+        return Ok(PathResult {
             found: false,
             path: vec![],
             iterations: 0,
-        };
+            verdict: Feasibility::Impossible(reason),
+            termination: None,
+            stats: None,
+            partial: None,
+            turns: None,
+        });
     }
 
-    let total_cells = (grid_size.rows * grid_size.cols) as usize;
+    // Entering the start cell is the first iteration, then at most
+    // `max_iterations` more cells; a search that stops within its budget
+    // has explored every branch. Rejected moves only count against the
+    // clock, which is read every `CLOCK_CHECK_INTERVAL` moves.
+    let deadline = options.time_limit_ms.map(|ms| clock.now_ms() + ms);
+    let mut search = Search::new(start, end, grid, options.seed)
+        .with_waypoints(options.waypoints.clone())
+        .with_fixed_edges(&options.fixed_edges);
+    let mut remaining = options.max_iterations;
+    let progress_interval = options.progress_interval.filter(|&n| n > 0);
+    let mut next_report = progress_interval.unwrap_or(u32::MAX);
+    let stopped_early = loop {
+        let until_report = next_report.saturating_sub(search.iterations()).max(1);
+        // A slice of moves enters at most as many cells
+        let slice = remaining.min(CLOCK_CHECK_INTERVAL).min(until_report);
+        let entered = search.iterations();
+        let status = search.step(slice);
+        if let Some(interval) = progress_interval {
+            if search.iterations() >= next_report {
+                on_progress(&search.stats());
+                next_report = next_report.saturating_add(interval);
+            }
+        }
+        if status != SolverStatus::Running {
+            break None;
+        }
+        remaining -= search.iterations() - entered;
+        if remaining == 0 {
+            break Some(Termination::IterationLimit);
+        }
+        if deadline.is_some_and(|deadline| clock.now_ms() >= deadline) {
+            break Some(Termination::Deadline);
+        }
+    };
+
+    let mut result = search.result();
+    if stopped_early.is_some() {
+        result.termination = stopped_early;
+    }
+
+    if let (true, Some(objective)) = (result.found, options.turn_objective) {
+        let path = std::mem::take(&mut result.path);
+        let mut rng = Rng::new(options.seed.unwrap_or_default());
+        result.path = turns::optimize_turns(
+            path,
+            grid,
+            objective,
+            options.turn_steps,
+            &mut rng,
+            || deadline.is_some_and(|deadline| clock.now_ms() >= deadline),
+            |chain| {
+                let position = |p| chain.position(p);
+                satisfies_constraints(position, &options.waypoints, &options.fixed_edges)
+            },
+        );
+        result.turns = Some(count_turns(&result.path));
+    }
+    Ok(result)
+}
+
+/// Decide whether a Hamiltonian path between `start` and `end` exists.
+///
+/// Full rectangles are decided exactly by the Itai–Papadimitriou–Szwarcfiter
+/// characterization, so this never returns `Unknown` for them. Obstacles and
+/// walls only allow necessary conditions (color balance, dead ends,
+/// connectivity) to be checked, so grids with either passing them are
+/// `Unknown`.
+fn check_feasibility_internal(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Result<Feasibility, SolveError> {
+    validate_grid(grid, MAX_GRID_CELLS)?;
+    validate_point(start, grid)?;
+    validate_point(end, grid)?;
+
+    if !grid.is_full_rectangle() {
+        return Ok(match grid::obstacle_obstruction(start, end, grid) {
+            Some(reason) => Feasibility::Impossible(reason),
+            None => Feasibility::Unknown,
+        });
+    }
+
+    let obstruction = constructive::rectangle_obstruction(start, end, &grid.size());
+    Ok(match obstruction {
+        Some(reason) => Feasibility::Impossible(reason),
+        None => Feasibility::Possible,
+    })
+}
+
+/// For a fixed `start`, decide for every cell whether it can end a
+/// Hamiltonian path. Blocked cells are `None`.
+///
+/// Full rectangles are decided exactly. With obstacles the necessary
+/// conditions are gathered once for all cells, then a single search from
+/// `start` with a free end marks every cell it reaches last. If that search
+/// finishes within `max_iterations` the cells it never reached are impossible,
+/// otherwise they stay unknown.
+fn reachable_endpoints_internal(
+    start: Point,
+    grid: &Grid,
+    max_iterations: u32,
+) -> Result<Vec<Vec<Option<Feasibility>>>, SolveError> {
+    validate_grid(grid, MAX_GRID_CELLS)?;
+    validate_point(start, grid)?;
+    let grid_size = grid.size();
+
+    let mut verdicts: Vec<Vec<Option<Feasibility>>> =
+        vec![vec![None; grid_size.cols as usize]; grid_size.rows as usize];
+
+    if grid.is_full_rectangle() {
+        for end in grid.open_points() {
+            let verdict = match constructive::rectangle_obstruction(start, end, &grid_size) {
+                Some(reason) => Feasibility::Impossible(reason),
+                None => Feasibility::Possible,
+            };
+            verdicts[end.row as usize][end.col as usize] = Some(verdict);
+        }
+        return Ok(verdicts);
+    }
+
+    let facts = grid::ObstacleFacts::new(grid);
+    let mut undecided = 0usize;
+    for end in grid.open_points() {
+        verdicts[end.row as usize][end.col as usize] = Some(match facts.obstruction(start, end) {
+            Some(reason) => Feasibility::Impossible(reason),
+            None => {
+                undecided += 1;
+                Feasibility::Unknown
+            }
+        });
+    }
+    if undecided == 0 {
+        return Ok(verdicts);
+    }
+
+    // One shared search: every path it completes settles its last cell
+    let mut paths = HamiltonianPaths::from_start(start, grid).with_max_steps(max_iterations);
+    for path in paths.by_ref() {
+        let end = path[path.len() - 1];
+        let verdict = &mut verdicts[end.row as usize][end.col as usize];
+        if *verdict == Some(Feasibility::Unknown) {
+            *verdict = Some(Feasibility::Possible);
+            undecided -= 1;
+            if undecided == 0 {
+                return Ok(verdicts);
+            }
+        }
+    }
+
+    if paths.is_exhausted() {
+        for verdict in verdicts.iter_mut().flatten() {
+            if *verdict == Some(Feasibility::Unknown) {
+                *verdict = Some(Feasibility::Impossible(InfeasibleReason::Exhausted));
+            }
+        }
+    }
+    Ok(verdicts)
+}
 
-    // Early exit: parity check for even-sized grids
-    // In a checkerboard pattern, a Hamiltonian path alternates between black and white cells.
-    // For even-sized grids, start and end must have different parities.
-    // For odd-sized grids, start and end must have the same parity.
-    let start_parity = get_cell_parity(start.row, start.col);
-    let end_parity = get_cell_parity(end.row, end.col);
-    let is_even_grid = total_cells % 2 == 0;
-
-    if is_even_grid && start_parity == end_parity {
-        // Even grid with same parity endpoints: impossible
-        return PathResult {
+/// Find a Hamiltonian cycle through every open cell, starting at `start`.
+///
+/// The returned path lists each cell once; the loop closes from the last cell
+/// back to `start`. Full rectangles are built directly, grids with obstacles
+/// search for a path from `start` to each of its open neighbors in turn.
+fn find_hamiltonian_cycle_internal(
+    start: Point,
+    grid: &Grid,
+    max_iterations: u32,
+) -> Result<PathResult, SolveError> {
+    validate_grid(grid, MAX_GRID_CELLS)?;
+    validate_point(start, grid)?;
+
+    if let Some(reason) = cycle::cycle_obstruction(grid) {
+        return Ok(PathResult {
             found: false,
             path: vec![],
             iterations: 0,
-        };
+            verdict: Feasibility::Impossible(reason),
+            termination: None,
+            stats: None,
+            partial: None,
+            turns: None,
+        });
     }
 
-    if !is_even_grid && start_parity != end_parity {
-        // Odd grid with different parity endpoints: impossible
-        return PathResult {
-            found: false,
-            path: vec![],
+    if grid.is_full_rectangle() {
+        return Ok(PathResult {
+            found: true,
+            path: cycle::construct_cycle(start, &grid.size()),
             iterations: 0,
-        };
+            verdict: Feasibility::Possible,
+            termination: None,
+            stats: None,
+            partial: None,
+            turns: None,
+        });
     }
 
-    // Use bitset for visited tracking (much faster than Vec<Vec<bool>>)
-    let mut visited = VisitedBitset::new(grid_size.cols as usize);
-    let mut result_path: Vec<Point> = Vec::new();
-    let mut found = false;
+    // Any loop leaves `start` through one of its neighbors, so trying each
+    // neighbor as the end of a path is exhaustive
     let mut iterations: u32 = 0;
+    let mut gave_up = None;
+    for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
+        let end = Point {
+            row: start.row + dr,
+            col: start.col + dc,
+        };
+        if !grid.can_step(start.row, start.col, direction) {
+            continue;
+        }
 
-    // Recursive backtracking with unvisited count tracking
-    fn backtrack(
-        path: &mut Vec<Point>,
-        end: &Point,
-        grid_size: &GridSize,
-        visited: &mut VisitedBitset,
-        unvisited_count: usize,
-        max_iterations: u32,
-        iterations: &mut u32,
-        found: &mut bool,
-        result_path: &mut Vec<Point>,
-    ) {
-        if *found || *iterations > max_iterations {
-            return;
-        }
-        *iterations += 1;
-
-        let current = *path.last().unwrap();
-
-        // Success: visited all cells and reached endpoint
-        if unvisited_count == 0 {
-            if current == *end {
-                *found = true;
-                *result_path = path.clone();
+        let budget = max_iterations.saturating_sub(iterations);
+        let result = find_hamiltonian_path_internal(start, end, grid, budget)?;
+        iterations = iterations.saturating_add(result.iterations);
+        match result.verdict {
+            Feasibility::Possible => {
+                return Ok(PathResult {
+                    iterations,
+                    ..result
+                })
             }
-            return;
+            Feasibility::Unknown => gave_up = result.termination,
+            Feasibility::Impossible(_) => {}
         }
+    }
 
-        // Pruning: reached endpoint too early
-        if current == *end {
-            return;
-        }
+    Ok(PathResult {
+        found: false,
+        path: vec![],
+        iterations,
+        verdict: if gave_up.is_some() {
+            Feasibility::Unknown
+        } else {
+            Feasibility::Impossible(InfeasibleReason::Exhausted)
+        },
+        termination: gave_up.or(Some(Termination::Exhausted)),
+        stats: None,
+        partial: None,
+        turns: None,
+    })
+}
 
-        // Get and sort neighbors using enhanced heuristics
-        let mut neighbors = get_unvisited_neighbors(current.row, current.col, grid_size, visited);
-        sort_by_warnsdorff(&mut neighbors, end, grid_size, visited, unvisited_count);
+/// Constructive Hamiltonian path for full rectangular grids
+///
+/// Decides existence exactly and builds the path in O(rows * cols), so unlike
+/// the backtracking search it never gives up on a solvable pair.
+fn construct_hamiltonian_path_internal(
+    start: Point,
+    end: Point,
+    grid_size: GridSize,
+) -> Result<PathResult, SolveError> {
+    validate_endpoints(start, end, &grid_size.into(), MAX_GRID_CELLS)?;
 
-        for (nr, nc, _) in neighbors {
-            let is_endpoint = nr == end.row && nc == end.col;
+    Ok(match constructive::construct_path(start, end, &grid_size) {
+        Some(path) => PathResult {
+            found: true,
+            path,
+            iterations: 0,
+            verdict: Feasibility::Possible,
+            termination: None,
+            stats: None,
+            partial: None,
+            turns: None,
+        },
+        None => PathResult {
+            found: false,
+            path: vec![],
+            iterations: 0,
+            verdict: check_feasibility_internal(start, end, &grid_size.into())?,
+            termination: None,
+            stats: None,
+            partial: None,
+            turns: None,
+        },
+    })
+}
 
-            visited.set(nr, nc);
-            let new_unvisited = unvisited_count - 1;
+/// Sample a random Hamiltonian path by applying backbite moves to `path`
+///
+/// `path` can be any Hamiltonian path of `grid` (a snake, a constructed or a
+/// searched path); more `steps` bring the result closer to uniform. No search
+/// runs, so `iterations` is 0.
+fn sample_hamiltonian_path_internal(
+    path: Vec<Point>,
+    grid: &Grid,
+    steps: u32,
+    fixed: FixedEnds,
+    seed: u64,
+) -> Result<PathResult, SolveError> {
+    validate_path(&path, grid)?;
+    if path.len() != grid.open_cells() {
+        return Err(SolveError::InvalidPath {
+            index: path.len(),
+            reason: "path does not cover every open cell",
+        });
+    }
 
-            // Pruning: check connectivity only when necessary
-            // Skip check if moving to endpoint (it doesn't need further connections)
-            // Also skip if only 1-2 cells remain (always connected or trivially checkable)
-            let should_check_connectivity = !is_endpoint
-                && new_unvisited > 2
-                && is_likely_articulation_point(nr, nc, grid_size, visited);
+    let path = sampler::sample_path(path, grid, steps, fixed, &mut Rng::new(seed));
+    Ok(PathResult {
+        found: true,
+        path,
+        iterations: 0,
+        verdict: Feasibility::Possible,
+        termination: None,
+        stats: None,
+        partial: None,
+        turns: None,
+    })
+}
 
-            if should_check_connectivity && !is_remaining_connected(grid_size, visited, new_unvisited)
-            {
-                visited.clear(nr, nc);
-                continue;
-            }
+/// Lazily enumerate every Hamiltonian path from `start` to `end`
+///
+/// Pairs ruled out by the feasibility check yield an empty iterator without
+/// searching.
+fn enumerate_hamiltonian_paths_internal(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Result<HamiltonianPaths, SolveError> {
+    validate_endpoints(start, end, grid, MAX_GRID_CELLS)?;
+
+    Ok(match check_feasibility_internal(start, end, grid)? {
+        Feasibility::Impossible(reason) => HamiltonianPaths::ruled_out(grid, reason),
+        _ => HamiltonianPaths::new(start, end, grid),
+    })
+}
 
-            path.push(Point { row: nr, col: nc });
+/// Count Hamiltonian paths from `start` to `end` with the frontier DP
+///
+/// Exact for any length as long as the narrower side of the grid is at most
+/// `frontier::MAX_FRONTIER_WIDTH`.
+fn count_hamiltonian_paths_internal(
+    start: Point,
+    end: Point,
+    grid: &Grid,
+) -> Result<BigUint, SolveError> {
+    validate_endpoints(start, end, grid, MAX_GRID_CELLS)?;
+    let width = grid.rows.min(grid.cols);
+    if width > frontier::MAX_FRONTIER_WIDTH {
+        return Err(SolveError::GridTooWide {
+            width,
+            max_width: frontier::MAX_FRONTIER_WIDTH,
+        });
+    }
 
-            backtrack(
-                path,
-                end,
-                grid_size,
-                visited,
-                new_unvisited,
-                max_iterations,
-                iterations,
-                found,
-                result_path,
-            );
+    Ok(frontier::count_paths(start, end, grid))
+}
 
-            if *found {
-                return;
-            }
+/// Convert path to road grid with connection data
+/// Blocked cells are never on the path and stay `None`. Connections along
+/// `fixed_edges`, which must all be steps of `path`, are also listed as fixed.
+fn path_to_road_grid_internal(
+    path: &[Point],
+    grid: &Grid,
+    fixed_edges: &[Edge],
+) -> Result<Vec<Vec<Option<CellData>>>, SolveError> {
+    build_road_grid(path, grid, false, fixed_edges)
+}
+
+/// Convert path to a grid of road tiles for tileset renderers
+/// Cells off the path stay `None`.
+fn path_to_tile_grid_internal(
+    path: &[Point],
+    grid: &Grid,
+) -> Result<Vec<Vec<Option<TileCell>>>, SolveError> {
+    validate_path(path, grid)?;
+
+    let mut tiles: Vec<Vec<Option<TileCell>>> =
+        vec![vec![None; grid.cols as usize]; grid.rows as usize];
+    for (cell, tile) in path.iter().zip(tiles::tile_cells(path)) {
+        tiles[cell.row as usize][cell.col as usize] = Some(tile);
+    }
+    Ok(tiles)
+}
 
-            path.pop();
-            visited.clear(nr, nc);
+/// Road grid as row-major connection masks and path indices
+fn path_to_road_masks_internal(path: &[Point], grid: &Grid) -> Result<RoadMasks, SolveError> {
+    validate_path(path, grid)?;
+    let cells = grid.rows as usize * grid.cols as usize;
+    let index = |p: &Point| (p.row * grid.cols + p.col) as usize;
+
+    let mut connections = vec![Connections::NONE; cells];
+    let mut path_indices = vec![OFF_ROAD; cells];
+    for (i, p) in path.iter().enumerate() {
+        path_indices[index(p)] = i as u32;
+    }
+    for step in path.windows(2) {
+        // Validated above, so consecutive cells are adjacent
+        if let Some(direction) = direction_between(step[0], step[1]) {
+            connections[index(&step[0])].insert(Connections::from_direction(direction));
+            // Directions come in opposite pairs: up/down, left/right
+            connections[index(&step[1])].insert(Connections::from_direction(direction ^ 1));
         }
     }
 
-    // Start backtracking
-    visited.set(start.row, start.col);
-    let mut path = vec![start];
-    let initial_unvisited = total_cells - 1; // We've visited the start cell
+    Ok(RoadMasks {
+        rows: grid.rows,
+        cols: grid.cols,
+        connections,
+        path_indices,
+    })
+}
 
-    backtrack(
-        &mut path,
-        &end,
-        &grid_size,
-        &mut visited,
-        initial_unvisited,
-        max_iterations,
-        &mut iterations,
-        &mut found,
-        &mut result_path,
-    );
-
-    PathResult {
-        found,
-        path: result_path,
-        iterations,
+/// Convert a closed loop to road grid, connecting the last cell to the first
+fn cycle_to_road_grid_internal(
+    path: &[Point],
+    grid: &Grid,
+) -> Result<Vec<Vec<Option<CellData>>>, SolveError> {
+    validate_path(path, grid)?;
+    if let (Some(&first), Some(&last)) = (path.first(), path.last()) {
+        let invalid = |reason| {
+            Err(SolveError::InvalidPath {
+                index: path.len() - 1,
+                reason,
+            })
+        };
+        let Some(direction) = direction_between(last, first).filter(|_| path.len() >= 4) else {
+            return invalid("loop does not close");
+        };
+        if grid.is_walled(last.row, last.col, direction) {
+            return invalid("closing step crosses a wall");
+        }
     }
+    build_road_grid(path, grid, true, &[])
 }
 
-/// Convert path to road grid with connection data
-fn path_to_road_grid_internal(path: &[Point], grid_size: &GridSize) -> Vec<Vec<Option<CellData>>> {
+/// Shared road grid conversion; `closed` adds the wrap-around connection and
+/// connections along `fixed_edges` are listed as fixed
+fn build_road_grid(
+    path: &[Point],
+    grid: &Grid,
+    closed: bool,
+    fixed_edges: &[Edge],
+) -> Result<Vec<Vec<Option<CellData>>>, SolveError> {
+    validate_path(path, grid)?;
+    let grid_size = &grid.size();
+
+    let mut path_index = vec![usize::MAX; grid_size.rows as usize * grid_size.cols as usize];
+    for (i, p) in path.iter().enumerate() {
+        path_index[(p.row * grid_size.cols + p.col) as usize] = i;
+    }
+    for (index, edge) in fixed_edges.iter().enumerate() {
+        validate_point(edge.from, grid)?;
+        validate_point(edge.to, grid)?;
+        let a = path_index[(edge.from.row * grid_size.cols + edge.from.col) as usize];
+        let b = path_index[(edge.to.row * grid_size.cols + edge.to.col) as usize];
+        let on_path = a != usize::MAX
+            && b != usize::MAX
+            && (a.abs_diff(b) == 1 || (closed && a.abs_diff(b) == path.len() - 1));
+        if !on_path {
+            return Err(SolveError::InvalidFixedEdge {
+                index,
+                reason: "edge is not on the path",
+            });
+        }
+    }
+    let fixed_masks = fixed_edge_masks(fixed_edges, grid_size);
+
     let mut grid: Vec<Vec<Option<CellData>>> =
         vec![vec![None; grid_size.cols as usize]; grid_size.rows as usize];
 
     if path.is_empty() {
-        return grid;
+        return Ok(grid);
     }
 
     for (i, current) in path.iter().enumerate() {
-        let mut connections = Vec::new();
+        // Connection to previous cell (a closed loop wraps around to the last)
+        let prev = if i > 0 {
+            path.get(i - 1)
+        } else if closed {
+            path.last()
+        } else {
+            None
+        };
 
-        // Connection to previous cell
-        if i > 0 {
-            let prev = &path[i - 1];
-            if prev.row < current.row {
-                connections.push("up".to_string());
-            } else if prev.row > current.row {
-                connections.push("down".to_string());
-            } else if prev.col < current.col {
-                connections.push("left".to_string());
-            } else if prev.col > current.col {
-                connections.push("right".to_string());
-            }
-        }
+        // Connection to next cell (a closed loop wraps around to the first)
+        let next = if i < path.len() - 1 {
+            path.get(i + 1)
+        } else if closed {
+            path.first()
+        } else {
+            None
+        };
 
-        // Connection to next cell
-        if i < path.len() - 1 {
-            let next = &path[i + 1];
-            if next.row < current.row {
-                connections.push("up".to_string());
-            } else if next.row > current.row {
-                connections.push("down".to_string());
-            } else if next.col < current.col {
-                connections.push("left".to_string());
-            } else if next.col > current.col {
-                connections.push("right".to_string());
+        let mut mask = Connections::NONE;
+        for neighbor in [prev, next].into_iter().flatten() {
+            if let Some(direction) = direction_between(*current, *neighbor) {
+                mask.insert(Connections::from_direction(direction));
             }
         }
+        let fixed = Connections::from_bits(
+            mask.bits() & fixed_masks[(current.row * grid_size.cols + current.col) as usize],
+        );
 
         grid[current.row as usize][current.col as usize] = Some(CellData {
-            connections,
+            mask,
             path_index: i,
+            fixed,
         });
     }
 
-    grid
+    Ok(grid)
 }
 
 // ============================================================================
 // WASM Exports
 // ============================================================================
 
+/// Serialize a result for JavaScript, reporting failures as `SolveError`
+fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, SolveError> {
+    Ok(serde_wasm_bindgen::to_value(value)?)
+}
+
+/// Road cell as the road grid exports return it: `connections` and `fixed`
+/// name the directions, toward the previous cell first
+#[derive(Serialize)]
+struct RoadCellJs {
+    mask: Connections,
+    connections: Vec<&'static str>,
+    path_index: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixed: Vec<&'static str>,
+}
+
+/// Spell out the connections of a road grid built from `path`
+fn road_grid_to_js(
+    road: Vec<Vec<Option<CellData>>>,
+    path: &[Point],
+    closed: bool,
+) -> Vec<Vec<Option<RoadCellJs>>> {
+    road.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| {
+                    let cell = cell?;
+                    let i = cell.path_index;
+                    let prev = if i > 0 {
+                        path.get(i - 1)
+                    } else if closed {
+                        path.last()
+                    } else {
+                        None
+                    };
+                    let next = if i + 1 < path.len() {
+                        path.get(i + 1)
+                    } else if closed {
+                        path.first()
+                    } else {
+                        None
+                    };
+                    let directions: Vec<usize> = [prev, next]
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|&neighbor| direction_between(path[i], neighbor))
+                        .collect();
+                    let names = |mask: Connections| {
+                        directions
+                            .iter()
+                            .filter(|&&d| mask.contains(Connections::from_direction(d)))
+                            .map(|&d| DIRECTIONS[d].2)
+                            .collect()
+                    };
+                    Some(RoadCellJs {
+                        mask: cell.mask,
+                        connections: names(cell.mask),
+                        path_index: i,
+                        fixed: names(cell.fixed),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
 pub fn init() {
     set_panic_hook();
 }
 
+/// Build a grid model from WASM arguments.
+/// `blocked` is a row-major mask (non-zero = blocked); omitted means no obstacles.
+/// `walls` is a row-major mask of walled sides per cell (1 up, 2 down, 4 left,
+/// 8 right); omitted means no walls.
+fn grid_from_mask(
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Grid {
+    Grid {
+        rows: grid_rows,
+        cols: grid_cols,
+        blocked: blocked
+            .map(|mask| mask.iter().map(|&b| b != 0).collect())
+            .unwrap_or_default(),
+        walls: walls.unwrap_or_default(),
+    }
+}
+
+/// Settings of the `find_hamiltonian_path_with_options` export, mirroring
+/// `SearchOptions` plus the grid masks. Every field may be omitted or
+/// `undefined`; `on_progress` is read from the same object but separately,
+/// since functions do not deserialize.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FindOptions {
+    /// Defaults to `DEFAULT_MAX_ITERATIONS`
+    max_iterations: Option<u32>,
+    /// Row-major mask of blocked cells (non-zero = blocked)
+    blocked: Option<Vec<u8>>,
+    /// Row-major mask of walled sides per cell (1 up, 2 down, 4 left, 8 right)
+    walls: Option<Vec<u8>>,
+    seed: Option<u64>,
+    time_limit_ms: Option<f64>,
+    /// Ignored without an `on_progress` callback
+    progress_interval: Option<u32>,
+    waypoints: Option<Vec<Point>>,
+    fixed_edges: Option<Vec<Edge>>,
+    turn_objective: Option<TurnObjective>,
+    /// Defaults to `DEFAULT_TURN_STEPS`
+    turn_steps: Option<u32>,
+}
+
+impl FindOptions {
+    /// Grid and search options for a `grid_rows` x `grid_cols` request
+    fn into_search(self, grid_rows: i32, grid_cols: i32, reports: bool) -> (Grid, SearchOptions) {
+        let grid = grid_from_mask(grid_rows, grid_cols, self.blocked, self.walls);
+        let options = SearchOptions {
+            max_iterations: self.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS),
+            seed: self.seed,
+            time_limit_ms: self.time_limit_ms,
+            progress_interval: self.progress_interval.filter(|_| reports),
+            waypoints: self.waypoints.unwrap_or_default(),
+            fixed_edges: self.fixed_edges.unwrap_or_default(),
+            turn_objective: self.turn_objective,
+            turn_steps: self.turn_steps.unwrap_or(DEFAULT_TURN_STEPS),
+        };
+        (grid, options)
+    }
+}
+
+/// Obstacle and wall masks of a grid, as passed to `check_feasibility`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GridMasks {
+    /// Row-major mask of blocked cells (non-zero = blocked)
+    blocked: Option<Vec<u8>>,
+    /// Row-major mask of walled sides per cell (1 up, 2 down, 4 left, 8 right)
+    walls: Option<Vec<u8>>,
+}
+
 /// Find Hamiltonian path - WASM entry point
 #[wasm_bindgen]
 pub fn find_hamiltonian_path(
@@ -566,7 +1487,7 @@ pub fn find_hamiltonian_path(
     grid_rows: i32,
     grid_cols: i32,
     max_iterations: u32,
-) -> JsValue {
+) -> Result<JsValue, JsError> {
     let start = Point {
         row: start_row,
         col: start_col,
@@ -575,30 +1496,560 @@ pub fn find_hamiltonian_path(
         row: end_row,
         col: end_col,
     };
-    let grid_size = GridSize {
-        rows: grid_rows,
-        cols: grid_cols,
-    };
+    let grid = grid_from_mask(grid_rows, grid_cols, None, None);
 
-    let result = find_hamiltonian_path_internal(start, end, grid_size, max_iterations);
+    let result = find_hamiltonian_path_internal(start, end, &grid, max_iterations)?;
 
-    serde_wasm_bindgen::to_value(&result).unwrap()
+    Ok(to_js_value(&result)?)
 }
 
-/// Convert path to road grid - WASM entry point
-#[wasm_bindgen]
-pub fn path_to_road_grid(path_js: JsValue, grid_rows: i32, grid_cols: i32) -> JsValue {
-    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).unwrap_or_default();
-    let grid_size = GridSize {
-        rows: grid_rows,
-        cols: grid_cols,
+/// Find Hamiltonian path with obstacles, constraints and budgets - WASM
+/// entry point
+///
+/// Exported as `find_hamiltonian_path_with_options`. `options_js` is an
+/// optional object with any of `max_iterations`, `blocked`, `walls`, `seed`,
+/// `time_limit_ms`, `waypoints` (points the road must visit in order),
+/// `fixed_edges` (`{ from, to }` segments it must use), `turn_objective`
+/// (`"minimize"`, `"maximize"`, `{ target: n }` or `{ ratio: r }`) and
+/// `turn_steps`. Its `on_progress` function, if any, is called with the
+/// search stats every `progress_interval` iterations.
+#[wasm_bindgen(js_name = find_hamiltonian_path_with_options)]
+pub fn find_hamiltonian_path_with_options_js(
+    start_row: i32,
+    start_col: i32,
+    end_row: i32,
+    end_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+    options_js: JsValue,
+) -> Result<JsValue, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
     };
+    let end = Point {
+        row: end_row,
+        col: end_col,
+    };
+    let on_progress = js_sys::Reflect::get(&options_js, &JsValue::from_str("on_progress"))
+        .ok()
+        .and_then(|value| value.dyn_into::<js_sys::Function>().ok());
+    let options: Option<FindOptions> =
+        serde_wasm_bindgen::from_value(options_js).map_err(SolveError::from)?;
+    let (grid, options) =
+        options
+            .unwrap_or_default()
+            .into_search(grid_rows, grid_cols, on_progress.is_some());
+
+    // Progress is informational, so errors thrown by the callback are ignored
+    let mut report = |stats: &SearchStats| {
+        if let (Some(callback), Ok(stats)) = (&on_progress, to_js_value(stats)) {
+            let _ = callback.call1(&JsValue::NULL, &stats);
+        }
+    };
+
+    let result = find_hamiltonian_path_with_options(
+        start,
+        end,
+        &grid,
+        &options,
+        &mut SystemClock::new(),
+        &mut report,
+    )?;
+
+    Ok(to_js_value(&result)?)
+}
+
+/// Convert closed loop to road grid - WASM entry point
+#[wasm_bindgen]
+pub fn cycle_to_road_grid(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let road = cycle_to_road_grid_internal(&path, &grid)?;
+    let grid = road_grid_to_js(road, &path, true);
+
+    Ok(to_js_value(&grid)?)
+}
+
+/// Construct Hamiltonian path on a full rectangle - WASM entry point
+#[wasm_bindgen]
+pub fn construct_hamiltonian_path(
+    start_row: i32,
+    start_col: i32,
+    end_row: i32,
+    end_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+) -> Result<JsValue, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
+    };
+    let end = Point {
+        row: end_row,
+        col: end_col,
+    };
+    let grid_size = GridSize {
+        rows: grid_rows,
+        cols: grid_cols,
+    };
+
+    let result = construct_hamiltonian_path_internal(start, end, grid_size)?;
+
+    Ok(to_js_value(&result)?)
+}
+
+/// Find Hamiltonian cycle (closed loop) - WASM entry point
+#[wasm_bindgen]
+pub fn find_hamiltonian_cycle(
+    start_row: i32,
+    start_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+    max_iterations: u32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
+    };
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let result = find_hamiltonian_cycle_internal(start, &grid, max_iterations)?;
+
+    Ok(to_js_value(&result)?)
+}
+
+/// Convert path to road grid - WASM entry point
+///
+/// Connections along the optional `fixed_edges_js` segments are also listed
+/// under `fixed`.
+#[wasm_bindgen]
+pub fn path_to_road_grid(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    fixed_edges_js: JsValue,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let fixed_edges: Option<Vec<Edge>> =
+        serde_wasm_bindgen::from_value(fixed_edges_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let road = path_to_road_grid_internal(&path, &grid, &fixed_edges.unwrap_or_default())?;
+    let grid = road_grid_to_js(road, &path, false);
+
+    Ok(to_js_value(&grid)?)
+}
+
+/// Sample a random Hamiltonian path from an existing one - WASM entry point
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn sample_hamiltonian_path(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    steps: u32,
+    fix_start: bool,
+    fix_end: bool,
+    seed: u64,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+    let fixed = FixedEnds {
+        start: fix_start,
+        end: fix_end,
+    };
+
+    let result = sample_hamiltonian_path_internal(path, &grid, steps, fixed, seed)?;
+
+    Ok(to_js_value(&result)?)
+}
+
+/// Cancellable, steppable path search - WASM entry point
+///
+/// Unlike `find_hamiltonian_path` the search runs in slices: each `step`
+/// makes at most `budget` moves (cells entered or rejected by a connectivity
+/// check) and returns, so a worker can interleave requests and `cancel`
+/// stale ones.
+#[wasm_bindgen]
+pub struct Solver {
+    search: Search,
+}
+
+#[wasm_bindgen]
+impl Solver {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        start_row: i32,
+        start_col: i32,
+        end_row: i32,
+        end_col: i32,
+        grid_rows: i32,
+        grid_cols: i32,
+        seed: Option<u64>,
+        blocked: Option<Vec<u8>>,
+        walls: Option<Vec<u8>>,
+    ) -> Result<Solver, JsError> {
+        let start = Point {
+            row: start_row,
+            col: start_col,
+        };
+        let end = Point {
+            row: end_row,
+            col: end_col,
+        };
+        let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+        validate_endpoints(start, end, &grid, MAX_GRID_CELLS)?;
+
+        let search = match check_feasibility_internal(start, end, &grid)? {
+            Feasibility::Impossible(reason) => Search::ruled_out(&grid, reason),
+            _ => Search::new(start, end, &grid, seed),
+        };
+
+        Ok(Solver { search })
+    }
+
+    /// Continue the search for at most `budget` moves
+    pub fn step(&mut self, budget: u32) -> SolverStatus {
+        self.search.step(budget)
+    }
+
+    /// Abandon the search; `step` keeps returning `Cancelled`
+    pub fn cancel(&mut self) {
+        self.search.cancel();
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> SolverStatus {
+        self.search.status()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> u32 {
+        self.search.iterations()
+    }
+
+    /// Counters for the work done so far
+    pub fn stats(&self) -> Result<JsValue, JsError> {
+        Ok(to_js_value(&self.search.stats())?)
+    }
+
+    /// Current result, shaped like the `find_hamiltonian_path` return value
+    pub fn result(&self) -> Result<JsValue, JsError> {
+        Ok(to_js_value(&self.search.result())?)
+    }
+}
+
+/// Convert path to a grid of road tiles - WASM entry point
+#[wasm_bindgen]
+pub fn path_to_tile_grid(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let tiles = path_to_tile_grid_internal(&path, &grid)?;
+
+    Ok(to_js_value(&tiles)?)
+}
+
+/// Path index of cells that are not on the road
+pub const OFF_ROAD: u32 = u32::MAX;
+
+/// Road grid as flat row-major arrays - WASM output of `path_to_road_masks`
+///
+/// The compact counterpart of `path_to_road_grid`: one `Connections` mask
+/// byte and one path index per cell, with `OFF_ROAD` for cells the path
+/// skips.
+#[wasm_bindgen]
+pub struct RoadMasks {
+    rows: i32,
+    cols: i32,
+    connections: Vec<Connections>,
+    path_indices: Vec<u32>,
+}
+
+impl RoadMasks {
+    /// Row-major index of a cell, or `None` outside the grid
+    fn index(&self, row: i32, col: i32) -> Option<usize> {
+        let inside = (0..self.rows).contains(&row) && (0..self.cols).contains(&col);
+        inside.then(|| (row * self.cols + col) as usize)
+    }
+
+    /// Connections of a cell, or `None` outside the grid
+    pub fn mask(&self, row: i32, col: i32) -> Option<Connections> {
+        self.index(row, col).map(|i| self.connections[i])
+    }
+
+    /// Position of a cell along the path; `None` off the road or the grid
+    pub fn path_index(&self, row: i32, col: i32) -> Option<u32> {
+        let index = self.index(row, col)?;
+        Some(self.path_indices[index]).filter(|&i| i != OFF_ROAD)
+    }
+}
+
+#[wasm_bindgen]
+impl RoadMasks {
+    #[wasm_bindgen(getter)]
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cols(&self) -> i32 {
+        self.cols
+    }
+
+    /// Connection masks as a `Uint8Array` (1 up, 2 down, 4 left, 8 right)
+    #[wasm_bindgen(getter)]
+    pub fn connections(&self) -> Vec<u8> {
+        self.connections.iter().map(|c| c.bits()).collect()
+    }
+
+    /// Path indices as a `Uint32Array`, `OFF_ROAD` (2^32 - 1) off the road
+    #[wasm_bindgen(getter)]
+    pub fn path_indices(&self) -> Vec<u32> {
+        self.path_indices.clone()
+    }
+}
+
+/// Convert path to flat connection masks and path indices - WASM entry point
+#[wasm_bindgen]
+pub fn path_to_road_masks(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<RoadMasks, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    Ok(path_to_road_masks_internal(&path, &grid)?)
+}
+
+/// Render a path as a standalone SVG document - WASM entry point
+///
+/// Exported as `render_svg`; `style_js` is a partial `SvgStyle` object or
+/// `undefined` for the defaults.
+#[wasm_bindgen(js_name = render_svg)]
+pub fn render_svg_js(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+    style_js: JsValue,
+) -> Result<String, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let style: Option<SvgStyle> =
+        serde_wasm_bindgen::from_value(style_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    Ok(render_svg(&path, &grid, &style.unwrap_or_default())?)
+}
+
+/// Encode a path as a compact string - WASM entry point
+///
+/// Exported as `encode_path`; `encoding_js` is `"moves"`, `"run_length"` or
+/// `"base64"`, or `undefined` for `"moves"`.
+#[wasm_bindgen(js_name = encode_path)]
+pub fn encode_path_js(path_js: JsValue, encoding_js: JsValue) -> Result<String, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let encoding: Option<PathEncoding> =
+        serde_wasm_bindgen::from_value(encoding_js).map_err(SolveError::from)?;
+
+    Ok(encode_path(&path, encoding.unwrap_or_default())?)
+}
+
+/// Decode a path string from `encode_path` and check it against the grid -
+/// WASM entry point, exported as `decode_path`
+#[wasm_bindgen(js_name = decode_path)]
+pub fn decode_path_js(
+    encoded: &str,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let path = decode_path(encoded, &grid)?;
+
+    Ok(to_js_value(&path)?)
+}
+
+/// Lazy enumeration of Hamiltonian paths - WASM entry point
+///
+/// Call `next_path` repeatedly; it returns `undefined` once every path has
+/// been produced.
+#[wasm_bindgen]
+pub struct PathEnumerator {
+    paths: HamiltonianPaths,
+}
+
+#[wasm_bindgen]
+impl PathEnumerator {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        start_row: i32,
+        start_col: i32,
+        end_row: i32,
+        end_col: i32,
+        grid_rows: i32,
+        grid_cols: i32,
+        blocked: Option<Vec<u8>>,
+        walls: Option<Vec<u8>>,
+    ) -> Result<PathEnumerator, JsError> {
+        let start = Point {
+            row: start_row,
+            col: start_col,
+        };
+        let end = Point {
+            row: end_row,
+            col: end_col,
+        };
+        let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+        let paths = enumerate_hamiltonian_paths_internal(start, end, &grid)?;
+
+        Ok(PathEnumerator { paths })
+    }
+
+    /// Next path, or `undefined` when the enumeration is finished
+    pub fn next_path(&mut self) -> Result<JsValue, JsError> {
+        match self.paths.next() {
+            Some(path) => Ok(to_js_value(&path)?),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+}
+
+/// Count Hamiltonian paths as a decimal string - WASM entry point
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn count_hamiltonian_paths(
+    start_row: i32,
+    start_col: i32,
+    end_row: i32,
+    end_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<String, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
+    };
+    let end = Point {
+        row: end_row,
+        col: end_col,
+    };
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let count = count_hamiltonian_paths_internal(start, end, &grid)?;
+
+    Ok(count.to_string())
+}
+
+/// Feasibility of every end cell for one start - WASM entry point
+#[wasm_bindgen]
+pub fn reachable_endpoints(
+    start_row: i32,
+    start_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+    max_iterations: u32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+) -> Result<JsValue, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
+    };
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let verdicts = reachable_endpoints_internal(start, &grid, max_iterations)?;
+
+    Ok(to_js_value(&verdicts)?)
+}
+
+/// Verify a submitted Hamiltonian path - WASM entry point
+///
+/// Exported as `verify_hamiltonian_path`; `constraints_js` is a partial
+/// `PathConstraints` object or `undefined`. Returns the list of violations,
+/// empty when the path is valid.
+#[wasm_bindgen(js_name = verify_hamiltonian_path)]
+pub fn verify_hamiltonian_path_js(
+    path_js: JsValue,
+    grid_rows: i32,
+    grid_cols: i32,
+    blocked: Option<Vec<u8>>,
+    walls: Option<Vec<u8>>,
+    constraints_js: JsValue,
+) -> Result<JsValue, JsError> {
+    let path: Vec<Point> = serde_wasm_bindgen::from_value(path_js).map_err(SolveError::from)?;
+    let constraints: Option<PathConstraints> =
+        serde_wasm_bindgen::from_value(constraints_js).map_err(SolveError::from)?;
+    let grid = grid_from_mask(grid_rows, grid_cols, blocked, walls);
+
+    let violations = verify_hamiltonian_path(&path, &grid, &constraints.unwrap_or_default())?;
+
+    Ok(to_js_value(&violations)?)
+}
+
+/// Decide whether a Hamiltonian path exists - WASM entry point
+///
+/// `masks_js` is an optional `{ blocked, walls }` object with the same masks
+/// as for `find_hamiltonian_path`.
+#[wasm_bindgen]
+pub fn check_feasibility(
+    start_row: i32,
+    start_col: i32,
+    end_row: i32,
+    end_col: i32,
+    grid_rows: i32,
+    grid_cols: i32,
+    masks_js: JsValue,
+) -> Result<JsValue, JsError> {
+    let start = Point {
+        row: start_row,
+        col: start_col,
+    };
+    let end = Point {
+        row: end_row,
+        col: end_col,
+    };
+    let masks: Option<GridMasks> =
+        serde_wasm_bindgen::from_value(masks_js).map_err(SolveError::from)?;
+    let masks = masks.unwrap_or_default();
+    let grid = grid_from_mask(grid_rows, grid_cols, masks.blocked, masks.walls);
+
+    let verdict = check_feasibility_internal(start, end, &grid)?;
+
+    Ok(to_js_value(&verdict)?)
+}
 
-    let grid = path_to_road_grid_internal(&path, &grid_size);
-
-    serde_wasm_bindgen::to_value(&grid).unwrap()
-}
-
 /// Get cell parity - WASM entry point
 #[wasm_bindgen]
 pub fn cell_parity(row: i32, col: i32) -> i32 {
@@ -625,9 +2076,9 @@ mod tests {
         let result = find_hamiltonian_path_internal(
             Point { row: 0, col: 0 },
             Point { row: 0, col: 1 },
-            GridSize { rows: 2, cols: 2 },
+            &GridSize { rows: 2, cols: 2 }.into(),
             100000,
-        );
+        ).unwrap();
         assert!(result.found);
         assert_eq!(result.path.len(), 4);
     }
@@ -638,9 +2089,9 @@ mod tests {
         let result = find_hamiltonian_path_internal(
             Point { row: 0, col: 0 },
             Point { row: 2, col: 2 },
-            GridSize { rows: 3, cols: 3 },
+            &GridSize { rows: 3, cols: 3 }.into(),
             100000,
-        );
+        ).unwrap();
         assert!(result.found);
         assert_eq!(result.path.len(), 9);
     }
@@ -650,10 +2101,1057 @@ mod tests {
         let result = find_hamiltonian_path_internal(
             Point { row: 0, col: 0 },
             Point { row: 0, col: 2 },
-            GridSize { rows: 2, cols: 4 },
+            &GridSize { rows: 2, cols: 4 }.into(),
             100000,
+        ).unwrap();
+        assert!(!result.found);
+    }
+
+    #[test]
+    fn test_verdict_distinguishes_impossible_from_gave_up() {
+        let grid_size = GridSize { rows: 2, cols: 4 };
+        let result = find_hamiltonian_path_internal(
+            Point { row: 0, col: 0 },
+            Point { row: 0, col: 2 },
+            &grid_size.into(),
+            100000,
+        )
+        .unwrap();
+        assert_eq!(
+            result.verdict,
+            Feasibility::Impossible(InfeasibleReason::Parity)
         );
+
+        assert_eq!(
+            check_feasibility_internal(
+                Point { row: 1, col: 1 },
+                Point { row: 1, col: 1 },
+                &grid_size.into()
+            )
+            .unwrap(),
+            Feasibility::Impossible(InfeasibleReason::SameEndpoints)
+        );
+
+        let result = find_hamiltonian_path_internal(
+            Point { row: 0, col: 0 },
+            Point { row: 7, col: 6 },
+            &GridSize { rows: 8, cols: 8 }.into(),
+            10,
+        )
+        .unwrap();
         assert!(!result.found);
+        assert_eq!(result.verdict, Feasibility::Unknown);
+    }
+
+    #[test]
+    fn test_check_feasibility_reasons() {
+        let cases = [
+            (
+                GridSize { rows: 3, cols: 3 },
+                Point { row: 0, col: 1 },
+                Point { row: 1, col: 0 },
+                InfeasibleReason::ColorImbalance,
+            ),
+            (
+                GridSize { rows: 1, cols: 5 },
+                Point { row: 0, col: 0 },
+                Point { row: 0, col: 2 },
+                InfeasibleReason::SingleLine,
+            ),
+            (
+                GridSize { rows: 2, cols: 5 },
+                Point { row: 0, col: 2 },
+                Point { row: 1, col: 2 },
+                InfeasibleReason::LadderRung,
+            ),
+            (
+                GridSize { rows: 3, cols: 4 },
+                Point { row: 1, col: 0 },
+                Point { row: 1, col: 1 },
+                InfeasibleReason::ThreeWideTrap,
+            ),
+        ];
+
+        for (grid_size, start, end, reason) in cases {
+            assert_eq!(
+                check_feasibility_internal(start, end, &grid_size.into()).unwrap(),
+                Feasibility::Impossible(reason)
+            );
+            // The backtracker reports the same reason without searching
+            let result =
+                find_hamiltonian_path_internal(start, end, &grid_size.into(), 100000).unwrap();
+            assert_eq!(result.verdict, Feasibility::Impossible(reason));
+            assert_eq!(result.iterations, 0);
+        }
+
+        assert_eq!(
+            check_feasibility_internal(
+                Point { row: 0, col: 0 },
+                Point { row: 19, col: 38 },
+                &GridSize { rows: 20, cols: 40 }.into()
+            )
+            .unwrap(),
+            Feasibility::Possible
+        );
+    }
+
+    #[test]
+    fn test_invalid_requests_return_errors() {
+        let grid_size = GridSize { rows: 4, cols: 4 };
+        let origin = Point { row: 0, col: 0 };
+
+        assert_eq!(
+            find_hamiltonian_path_internal(
+                origin,
+                Point { row: 4, col: 1 },
+                &grid_size.into(),
+                1000
+            )
+            .unwrap_err(),
+            SolveError::OutOfBounds {
+                point: Point { row: 4, col: 1 },
+                grid_size
+            }
+        );
+        assert_eq!(
+            find_hamiltonian_path_internal(origin, origin, &grid_size.into(), 1000).unwrap_err(),
+            SolveError::SameEndpoints { point: origin }
+        );
+        assert!(matches!(
+            find_hamiltonian_path_internal(
+                origin,
+                Point { row: 0, col: 1 },
+                &GridSize {
+                    rows: 3000,
+                    cols: 3000
+                }
+                .into(),
+                1000
+            ),
+            Err(SolveError::GridTooLarge {
+                cells: 9_000_000,
+                ..
+            })
+        ));
+        assert!(matches!(
+            construct_hamiltonian_path_internal(
+                origin,
+                Point { row: 0, col: 1 },
+                GridSize { rows: 0, cols: 3 }
+            ),
+            Err(SolveError::InvalidGridSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_road_grid_rejects_invalid_paths() {
+        let grid_size = GridSize { rows: 2, cols: 2 };
+        let p = |row, col| Point { row, col };
+
+        assert!(path_to_road_grid_internal(&[], &grid_size.into(), &[]).is_ok());
+        assert!(path_to_road_grid_internal(
+            &[p(0, 0), p(0, 1), p(1, 1), p(1, 0)],
+            &grid_size.into(),
+            &[]
+        )
+        .is_ok());
+        assert!(matches!(
+            path_to_road_grid_internal(&[p(0, 0), p(1, 1)], &grid_size.into(), &[]),
+            Err(SolveError::InvalidPath { index: 1, .. })
+        ));
+        assert!(matches!(
+            path_to_road_grid_internal(&[p(0, 0), p(0, 1), p(0, 0)], &grid_size.into(), &[]),
+            Err(SolveError::InvalidPath { index: 2, .. })
+        ));
+        assert!(matches!(
+            path_to_road_grid_internal(&[p(0, 0), p(0, -1)], &grid_size.into(), &[]),
+            Err(SolveError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_visited_bitset_beyond_800_cells() {
+        let grid_size = GridSize {
+            rows: 100,
+            cols: 100,
+        };
+        let mut visited = VisitedBitset::new(&grid_size);
+        assert_eq!(visited.bits.len(), 157);
+
+        visited.set(99, 99);
+        visited.set(8, 32);
+        assert!(visited.get(99, 99));
+        assert!(visited.get(8, 32));
+        assert!(!visited.get(8, 33));
+
+        visited.clear(99, 99);
+        assert!(!visited.get(99, 99));
+        visited.reset();
+        assert!(!visited.get(8, 32));
+    }
+
+    #[test]
+    fn test_large_grids_supported() {
+        let start = Point { row: 0, col: 0 };
+        let end = Point { row: 0, col: 1 };
+
+        for grid_size in [
+            GridSize { rows: 64, cols: 64 },
+            GridSize {
+                rows: 100,
+                cols: 100,
+            },
+        ] {
+            let total_cells = (grid_size.rows * grid_size.cols) as usize;
+
+            let result = construct_hamiltonian_path_internal(start, end, grid_size).unwrap();
+            assert!(result.found);
+            assert_eq!(result.path.len(), total_cells);
+
+            // The backtracker accepts the same sizes; only its budget limits it
+            let result =
+                find_hamiltonian_path_internal(start, end, &grid_size.into(), 200).unwrap();
+            assert!(result.found || result.verdict == Feasibility::Unknown);
+        }
+    }
+
+    #[test]
+    fn test_obstacles_are_avoided() {
+        let p = |row, col| Point { row, col };
+        // 4x4 with a 2x2 lake in the top-right corner: 12 open cells
+        let grid = Grid::new(4, 4)
+            .with_blocked(&[p(0, 2), p(0, 3), p(1, 2), p(1, 3)])
+            .unwrap();
+
+        let result = find_hamiltonian_path_internal(p(0, 0), p(0, 1), &grid, 100000).unwrap();
+        assert!(result.found);
+        assert_eq!(result.path.len(), 12);
+        assert!(result.path.iter().all(|q| grid.is_open(q.row, q.col)));
+
+        let road = path_to_road_grid_internal(&result.path, &grid, &[]).unwrap();
+        assert!(road[0][3].is_none());
+        assert_eq!(road.iter().flatten().filter(|c| c.is_some()).count(), 12);
+
+        assert!(matches!(
+            path_to_road_grid_internal(&[p(1, 1), p(1, 2)], &grid, &[]),
+            Err(SolveError::BlockedCell { .. })
+        ));
+        assert!(matches!(
+            find_hamiltonian_path_internal(p(0, 0), p(0, 3), &grid, 1000),
+            Err(SolveError::BlockedCell { .. })
+        ));
+    }
+
+    #[test]
+    fn test_obstacle_verdicts() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap();
+
+        // The ring around the blocked center has no path between same colors
+        assert_eq!(
+            check_feasibility_internal(p(0, 0), p(2, 2), &grid).unwrap(),
+            Feasibility::Impossible(InfeasibleReason::Parity)
+        );
+        assert_eq!(
+            check_feasibility_internal(p(0, 0), p(1, 0), &grid).unwrap(),
+            Feasibility::Unknown
+        );
+
+        // A search proves what the necessary conditions cannot
+        let result = find_hamiltonian_path_internal(p(0, 0), p(0, 1), &grid, 100000).unwrap();
+        assert!(result.found);
+        // Blocking the bottom row leaves a 2x3 ladder whose middle rung has no path
+        let grid = Grid::new(3, 3)
+            .with_blocked(&[p(2, 0), p(2, 1), p(2, 2)])
+            .unwrap();
+        assert_eq!(
+            check_feasibility_internal(p(0, 1), p(1, 1), &grid).unwrap(),
+            Feasibility::Unknown
+        );
+        let result = find_hamiltonian_path_internal(p(0, 1), p(1, 1), &grid, 100000).unwrap();
+        assert!(!result.found);
+        assert_eq!(
+            result.verdict,
+            Feasibility::Impossible(InfeasibleReason::Exhausted)
+        );
+
+        let bad_mask = Grid {
+            blocked: vec![false; 5],
+            ..Grid::new(2, 2)
+        };
+        assert!(matches!(
+            check_feasibility_internal(p(0, 0), p(0, 1), &bad_mask),
+            Err(SolveError::InvalidObstacleMask {
+                expected: 4,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn test_hamiltonian_cycle() {
+        let p = |row, col| Point { row, col };
+
+        let result = find_hamiltonian_cycle_internal(p(1, 2), &Grid::new(4, 5), 1000).unwrap();
+        assert!(result.found);
+        assert_eq!(result.path.len(), 20);
+        assert_eq!(result.path[0], p(1, 2));
+
+        let road = cycle_to_road_grid_internal(&result.path, &Grid::new(4, 5)).unwrap();
+        assert!(road
+            .iter()
+            .flatten()
+            .all(|cell| cell.as_ref().unwrap().mask.len() == 2));
+
+        let result = find_hamiltonian_cycle_internal(p(0, 0), &Grid::new(3, 5), 1000).unwrap();
+        assert_eq!(
+            result.verdict,
+            Feasibility::Impossible(InfeasibleReason::OddCellCount)
+        );
+
+        // A 4x4 ring around a blocked 2x2 center
+        let grid = Grid::new(4, 4)
+            .with_blocked(&[p(1, 1), p(1, 2), p(2, 1), p(2, 2)])
+            .unwrap();
+        let result = find_hamiltonian_cycle_internal(p(0, 0), &grid, 100000).unwrap();
+        assert!(result.found);
+        assert_eq!(result.path.len(), 12);
+        assert!(cycle_to_road_grid_internal(&result.path, &grid).is_ok());
+
+        // Open path endpoints are not adjacent, so it cannot be drawn as a loop
+        assert!(matches!(
+            cycle_to_road_grid_internal(&[p(0, 0), p(0, 1), p(0, 2)], &Grid::new(1, 3)),
+            Err(SolveError::InvalidPath { .. })
+        ));
+
+        // Nor can a loop whose closing step crosses a wall
+        let square = [p(0, 0), p(0, 1), p(1, 1), p(1, 0)];
+        let fenced = Grid::new(2, 2)
+            .with_walls(&[Edge {
+                from: p(1, 0),
+                to: p(0, 0),
+            }])
+            .unwrap();
+        assert!(cycle_to_road_grid_internal(&square, &Grid::new(2, 2)).is_ok());
+        assert!(matches!(
+            cycle_to_road_grid_internal(&square, &fenced),
+            Err(SolveError::InvalidPath {
+                index: 3,
+                reason: "closing step crosses a wall"
+            })
+        ));
+    }
+
+    #[test]
+    fn test_seeded_paths_are_reproducible_and_varied() {
+        let grid = Grid::new(6, 6);
+        let start = Point { row: 0, col: 0 };
+        let end = Point { row: 5, col: 0 };
+
+        let seeded = |seed| {
+            let options = SearchOptions {
+                max_iterations: 500_000,
+                seed: Some(seed),
+                ..SearchOptions::default()
+            };
+            find_hamiltonian_path_with_options(
+                start,
+                end,
+                &grid,
+                &options,
+                &mut SystemClock::new(),
+                &mut |_| {},
+            )
+            .unwrap()
+        };
+
+        let first = seeded(7);
+        let again = seeded(7);
+        assert!(first.found);
+        assert_eq!(first.path, again.path);
+
+        let distinct: std::collections::HashSet<Vec<Point>> = (0..10u64)
+            .map(seeded)
+            .filter(|result| result.found)
+            .map(|result| result.path)
+            .collect();
+        assert!(distinct.len() > 1, "seeds should produce different roads");
+        assert!(distinct.iter().all(|path| path.len() == 36));
+    }
+
+    #[test]
+    fn test_seed_reorders_near_ties() {
+        // From an interior cell the neighbors towards the target rank 10
+        // behind the other two, so only the jitter can put them first
+        let grid_size = GridSize { rows: 6, cols: 6 };
+        let mut visited = VisitedBitset::new(&grid_size);
+        visited.set(2, 2);
+        let target = Point { row: 5, col: 5 };
+        let first_choices: std::collections::HashSet<(i32, i32)> = (0..64)
+            .map(|seed| {
+                let mut neighbors = get_unvisited_neighbors(2, 2, &grid_size, &[], &visited);
+                let mut rng = Rng::new(seed);
+                sort_by_warnsdorff(
+                    &mut neighbors,
+                    &target,
+                    &grid_size,
+                    &[],
+                    &visited,
+                    35,
+                    Some(&mut rng),
+                );
+                (neighbors[0].0, neighbors[0].1)
+            })
+            .collect();
+        assert!(first_choices.contains(&(1, 2)) && first_choices.contains(&(2, 1)));
+        assert!(first_choices.contains(&(3, 2)) || first_choices.contains(&(2, 3)));
+    }
+
+    #[test]
+    fn test_sampled_paths_keep_requested_endpoints() {
+        let grid_size = GridSize { rows: 6, cols: 6 };
+        let start = Point { row: 0, col: 0 };
+        let end = Point { row: 5, col: 0 };
+        let initial = construct_hamiltonian_path_internal(start, end, grid_size)
+            .unwrap()
+            .path;
+        let fixed = FixedEnds {
+            start: true,
+            end: true,
+        };
+
+        let result =
+            sample_hamiltonian_path_internal(initial.clone(), &grid_size.into(), 200, fixed, 9)
+                .unwrap();
+        assert!(result.found);
+        assert_eq!(result.iterations, 0);
+        assert_eq!(result.path.len(), 36);
+        assert_eq!((result.path[0], result.path[35]), (start, end));
+        assert_ne!(result.path, initial);
+        assert!(path_to_road_grid_internal(&result.path, &grid_size.into(), &[]).is_ok());
+
+        let err = sample_hamiltonian_path_internal(
+            initial[..30].to_vec(),
+            &grid_size.into(),
+            10,
+            fixed,
+            9,
+        );
+        assert!(matches!(
+            err,
+            Err(SolveError::InvalidPath { index: 30, .. })
+        ));
+    }
+
+    #[test]
+    fn test_enumerate_and_count_paths() {
+        let grid = Grid::new(4, 5);
+        let start = Point { row: 0, col: 0 };
+        let end = Point { row: 3, col: 4 };
+
+        let enumerated = enumerate_hamiltonian_paths_internal(start, end, &grid)
+            .unwrap()
+            .count();
+        let counted = count_hamiltonian_paths_internal(start, end, &grid).unwrap();
+        assert!(enumerated > 0);
+        assert_eq!(counted, BigUint::from(enumerated as u64));
+
+        // Ruled out by parity: nothing to enumerate, count is zero
+        let same_color = Point { row: 3, col: 3 };
+        assert_eq!(
+            enumerate_hamiltonian_paths_internal(start, same_color, &grid)
+                .unwrap()
+                .count(),
+            0
+        );
+        assert!(count_hamiltonian_paths_internal(start, same_color, &grid)
+            .unwrap()
+            .is_zero());
+
+        // Counts beyond u64 on a long strip, the same in either orientation
+        let long =
+            count_hamiltonian_paths_internal(start, Point { row: 0, col: 5 }, &Grid::new(30, 6))
+                .unwrap();
+        let wide =
+            count_hamiltonian_paths_internal(start, Point { row: 5, col: 0 }, &Grid::new(6, 30))
+                .unwrap();
+        assert_eq!(long.to_string(), "2200855054478365635412");
+        assert_eq!(long, wide);
+
+        assert!(matches!(
+            count_hamiltonian_paths_internal(start, end, &Grid::new(20, 20)),
+            Err(SolveError::GridTooWide { width: 20, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reachable_endpoints() {
+        let start = Point { row: 0, col: 1 };
+
+        // Full rectangles agree with the per-cell feasibility check
+        let grid = Grid::new(4, 5);
+        let verdicts = reachable_endpoints_internal(start, &grid, 0).unwrap();
+        for end in grid.open_points() {
+            let expected = check_feasibility_internal(start, end, &grid).unwrap();
+            assert_eq!(verdicts[end.row as usize][end.col as usize], Some(expected));
+        }
+
+        // With obstacles, agree with an exhaustive search per cell
+        let grid = Grid::new(4, 4)
+            .with_blocked(&[Point { row: 1, col: 1 }])
+            .unwrap();
+        let verdicts = reachable_endpoints_internal(start, &grid, 1_000_000).unwrap();
+        assert_eq!(verdicts[1][1], None);
+        for end in grid.open_points().filter(|&end| end != start) {
+            let found = find_hamiltonian_path_internal(start, end, &grid, 1_000_000)
+                .unwrap()
+                .found;
+            let verdict = verdicts[end.row as usize][end.col as usize].unwrap();
+            assert_eq!(verdict == Feasibility::Possible, found, "{:?}", end);
+            assert!(matches!(
+                verdict,
+                Feasibility::Possible | Feasibility::Impossible(_)
+            ));
+        }
+
+        // Out of budget, undecided cells stay unknown
+        let grid = Grid::new(8, 8)
+            .with_blocked(&[Point { row: 7, col: 7 }])
+            .unwrap();
+        let verdicts = reachable_endpoints_internal(start, &grid, 10).unwrap();
+        assert!(verdicts
+            .iter()
+            .flatten()
+            .any(|v| *v == Some(Feasibility::Unknown)));
+    }
+
+    #[test]
+    fn test_solver_handle_steps_and_cancels() {
+        let mut solver = Solver::new(0, 0, 7, 0, 8, 8, None, None, None).unwrap();
+        assert_eq!(solver.status(), SolverStatus::Running);
+        while solver.step(10) == SolverStatus::Running {}
+        assert_eq!(solver.status(), SolverStatus::Found);
+        assert_eq!(solver.search.result().path.len(), 64);
+
+        // Ruled out by parity before any search
+        let solver = Solver::new(0, 0, 7, 7, 8, 8, None, None, None).unwrap();
+        assert_eq!(solver.status(), SolverStatus::Exhausted);
+        assert_eq!(
+            solver.search.result().verdict,
+            Feasibility::Impossible(InfeasibleReason::Parity)
+        );
+
+        // Walls come in from JS too: a fence leaves the middle of the 1x3 line
+        // with a single neighbor
+        let solver = Solver::new(0, 0, 0, 2, 1, 3, None, None, Some(vec![0, 8, 4])).unwrap();
+        assert_eq!(solver.status(), SolverStatus::Exhausted);
+        assert_eq!(
+            solver.search.result().verdict,
+            Feasibility::Impossible(InfeasibleReason::DeadEnd)
+        );
+
+        let mut solver = Solver::new(0, 0, 7, 0, 8, 8, Some(1), None, None).unwrap();
+        solver.cancel();
+        assert_eq!(solver.step(1_000), SolverStatus::Cancelled);
+        assert_eq!(solver.iterations(), 1);
+    }
+
+    #[test]
+    fn test_deep_search_does_not_overflow_the_stack() {
+        // One search frame per cell, run on a stack far smaller than the WASM
+        // default so any recursion per cell would overflow it
+        let search = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(|| {
+                let grid = Grid::new(1, 200_000);
+                let end = Point {
+                    row: 0,
+                    col: 199_999,
+                };
+                find_hamiltonian_path_internal(Point { row: 0, col: 0 }, end, &grid, 250_000)
+            })
+            .unwrap();
+        let result = search.join().unwrap().unwrap();
+        assert!(result.found);
+        assert_eq!(result.path.len(), 200_000);
+    }
+
+    #[test]
+    fn test_search_reports_how_it_ended() {
+        let grid = Grid::new(10, 10);
+        let start = Point { row: 0, col: 0 };
+        let end = Point { row: 9, col: 0 };
+
+        let result = find_hamiltonian_path_internal(start, end, &grid, 100_000).unwrap();
+        assert_eq!(result.termination, Some(Termination::Found));
+
+        let result = find_hamiltonian_path_internal(start, end, &grid, 10).unwrap();
+        assert_eq!(result.termination, Some(Termination::IterationLimit));
+        assert_eq!(result.verdict, Feasibility::Unknown);
+
+        // 3x3 ring corners share a color: ruled out before any search runs
+        let ring = Grid::new(3, 3)
+            .with_blocked(&[Point { row: 1, col: 1 }])
+            .unwrap();
+        let result =
+            find_hamiltonian_path_internal(start, Point { row: 2, col: 0 }, &ring, 1000).unwrap();
+        assert!(result.termination.is_none());
+
+        // Each clock read advances 5ms: the deadline is 17ms and is passed at the
+        // third check, long before a path through 1598 cells can be completed
+        let grid = Grid::new(40, 40)
+            .with_blocked(&[Point { row: 20, col: 20 }, Point { row: 20, col: 21 }])
+            .unwrap();
+        let options = SearchOptions {
+            max_iterations: u32::MAX,
+            time_limit_ms: Some(12.0),
+            ..SearchOptions::default()
+        };
+        let mut clock = clock::tests::TickingClock {
+            now: 0.0,
+            tick: 5.0,
+        };
+        let far_corner = Point { row: 39, col: 38 };
+        let result = find_hamiltonian_path_with_options(
+            start,
+            far_corner,
+            &grid,
+            &options,
+            &mut clock,
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_eq!(result.termination, Some(Termination::Deadline));
+        // Moves rejected by the connectivity check count against the clock
+        let stats = result.stats.unwrap();
+        assert_eq!(stats.moves, 3 * CLOCK_CHECK_INTERVAL);
+        assert_eq!(
+            result.iterations,
+            1 + stats.moves - stats.connectivity_prunes
+        );
+
+        // ...but not against the iteration budget, which counts cells entered
+        let options = SearchOptions {
+            max_iterations: 1_000,
+            ..SearchOptions::default()
+        };
+        let result = find_hamiltonian_path_with_options(
+            start,
+            far_corner,
+            &grid,
+            &options,
+            &mut SystemClock::new(),
+            &mut |_| {},
+        )
+        .unwrap();
+        assert_eq!(result.termination, Some(Termination::IterationLimit));
+        assert_eq!(result.iterations, 1 + 1_000);
+        assert!(result.stats.unwrap().moves >= 1_000);
+    }
+
+    #[test]
+    fn test_progress_reports_stats() {
+        let grid = Grid::new(12, 12)
+            .with_blocked(&[Point { row: 6, col: 6 }, Point { row: 6, col: 7 }])
+            .unwrap();
+        let options = SearchOptions {
+            max_iterations: 5_000,
+            progress_interval: Some(1_000),
+            ..SearchOptions::default()
+        };
+        let mut reports = Vec::new();
+        let result = find_hamiltonian_path_with_options(
+            Point { row: 0, col: 0 },
+            Point { row: 11, col: 10 },
+            &grid,
+            &options,
+            &mut SystemClock::new(),
+            &mut |stats| reports.push(*stats),
+        )
+        .unwrap();
+
+        let stats = result.stats.unwrap();
+        assert_eq!(stats.iterations, result.iterations);
+        assert!(stats.max_depth as usize <= grid.open_cells());
+        assert_eq!(reports.len() as u32, result.iterations / 1_000);
+        for (i, report) in reports.iter().enumerate() {
+            assert_eq!(report.iterations, 1_000 * (i as u32 + 1));
+        }
+        assert!(reports.windows(2).all(|w| w[0].max_depth <= w[1].max_depth));
+    }
+
+    #[test]
+    fn test_partial_path_on_failure() {
+        let grid = Grid::new(30, 30);
+        let start = Point { row: 0, col: 0 };
+        let result =
+            find_hamiltonian_path_internal(start, Point { row: 29, col: 0 }, &grid, 50).unwrap();
+        assert!(!result.found && result.path.is_empty());
+        let partial = result.partial.unwrap();
+        assert_eq!(partial.path[0], start);
+        assert!(partial.path.len() > 1);
+        assert_eq!(partial.path.len() + partial.uncovered.len(), 900);
+        assert!(path_to_road_grid_internal(&partial.path, &grid, &[]).is_ok());
+
+        let result =
+            find_hamiltonian_path_internal(start, Point { row: 29, col: 0 }, &grid, 100_000)
+                .unwrap();
+        assert!(result.found && result.partial.is_none());
+    }
+
+    #[test]
+    fn test_waypoint_search() {
+        let grid = Grid::new(6, 6);
+        let (start, end) = (Point { row: 0, col: 0 }, Point { row: 0, col: 5 });
+        let search = |waypoints: Vec<Point>| {
+            let options = SearchOptions {
+                max_iterations: 100_000,
+                waypoints,
+                ..SearchOptions::default()
+            };
+            find_hamiltonian_path_with_options(
+                start,
+                end,
+                &grid,
+                &options,
+                &mut SystemClock::new(),
+                &mut |_| {},
+            )
+        };
+
+        let town = Point { row: 5, col: 0 };
+        let castle = Point { row: 5, col: 5 };
+        let result = search(vec![town, castle]).unwrap();
+        assert!(result.found);
+        let at = |p: Point| result.path.iter().position(|&c| c == p).unwrap();
+        assert!(at(town) < at(castle));
+
+        assert_eq!(
+            search(vec![town, town]).unwrap_err(),
+            SolveError::InvalidWaypoint {
+                index: 1,
+                reason: "waypoint listed twice"
+            }
+        );
+        assert_eq!(
+            search(vec![end]).unwrap_err(),
+            SolveError::InvalidWaypoint {
+                index: 0,
+                reason: "waypoint is an endpoint"
+            }
+        );
+        assert!(matches!(
+            search(vec![Point { row: 6, col: 0 }]),
+            Err(SolveError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fixed_edges() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(6, 6);
+        let (start, end) = (p(0, 0), p(0, 5));
+        let edge = |a: Point, b: Point| Edge { from: a, to: b };
+        let search = |fixed_edges: Vec<Edge>| {
+            let options = SearchOptions {
+                max_iterations: 100_000,
+                fixed_edges,
+                ..SearchOptions::default()
+            };
+            find_hamiltonian_path_with_options(
+                start,
+                end,
+                &grid,
+                &options,
+                &mut SystemClock::new(),
+                &mut |_| {},
+            )
+        };
+
+        // A hand-drawn bend in the middle of the grid
+        let bend = vec![edge(p(2, 2), p(2, 3)), edge(p(3, 3), p(2, 3))];
+        let result = search(bend.clone()).unwrap();
+        assert!(result.found);
+        let road = path_to_road_grid_internal(&result.path, &grid, &bend).unwrap();
+        let corner = road[2][3].as_ref().unwrap();
+        assert_eq!(corner.fixed.len(), 2);
+        assert!(corner.mask.contains(corner.fixed));
+        assert_eq!(road[2][2].as_ref().unwrap().fixed, Connections::RIGHT);
+        let fixed_cells = road
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|c| !c.fixed.is_empty())
+            .count();
+        assert_eq!(fixed_cells, 3);
+
+        let invalid = |fixed_edges: Vec<Edge>| match search(fixed_edges) {
+            Err(SolveError::InvalidFixedEdge { index, reason }) => (index, reason),
+            other => panic!("expected InvalidFixedEdge, got {:?}", other),
+        };
+        assert_eq!(
+            invalid(vec![edge(p(1, 1), p(2, 2))]),
+            (0, "cells are not adjacent")
+        );
+        assert_eq!(
+            invalid(vec![edge(p(1, 1), p(1, 2)), edge(p(1, 2), p(1, 1))]),
+            (1, "edge listed twice")
+        );
+        assert_eq!(
+            invalid(vec![
+                edge(p(1, 1), p(1, 2)),
+                edge(p(1, 1), p(0, 1)),
+                edge(p(1, 1), p(2, 1))
+            ]),
+            (2, "cell has more than two fixed edges")
+        );
+        assert_eq!(
+            invalid(vec![edge(p(0, 0), p(0, 1)), edge(p(0, 0), p(1, 0))]),
+            (1, "endpoint has more than one fixed edge")
+        );
+        assert_eq!(
+            invalid(vec![
+                edge(p(1, 1), p(1, 2)),
+                edge(p(1, 2), p(2, 2)),
+                edge(p(2, 2), p(2, 1)),
+                edge(p(2, 1), p(1, 1)),
+            ]),
+            (3, "fixed edges close a loop")
+        );
+        assert_eq!(
+            path_to_road_grid_internal(&result.path, &grid, &[edge(p(5, 5), p(0, 5))]).unwrap_err(),
+            SolveError::InvalidFixedEdge {
+                index: 0,
+                reason: "edge is not on the path"
+            }
+        );
+    }
+
+    #[test]
+    fn test_walls_steer_the_search() {
+        let p = |row, col| Point { row, col };
+        // Fences between rows leave one gap each, alternating sides: the only
+        // road is the boustrophedon snake
+        let fences: Vec<Edge> = (0..3)
+            .flat_map(|row| {
+                let gap = if row % 2 == 0 { 3 } else { 0 };
+                (0..4).filter(move |&col| col != gap).map(move |col| Edge {
+                    from: p(row, col),
+                    to: p(row + 1, col),
+                })
+            })
+            .collect();
+        let grid = Grid::new(4, 4).with_walls(&fences).unwrap();
+
+        assert_eq!(
+            check_feasibility_internal(p(0, 0), p(3, 0), &grid).unwrap(),
+            Feasibility::Unknown
+        );
+        let result = find_hamiltonian_path_internal(p(0, 0), p(3, 0), &grid, 10_000).unwrap();
+        assert!(result.found);
+        let snake: Vec<Point> = (0..4)
+            .flat_map(|row| (0..4).map(move |i| p(row, if row % 2 == 0 { i } else { 3 - i })))
+            .collect();
+        assert_eq!(result.path, snake);
+        assert!(path_to_road_grid_internal(&result.path, &grid, &[]).is_ok());
+
+        assert_eq!(
+            path_to_road_grid_internal(&[p(0, 0), p(1, 0)], &grid, &[]).unwrap_err(),
+            SolveError::InvalidPath {
+                index: 1,
+                reason: "step crosses a wall"
+            }
+        );
+        let mut bad_mask = Grid::new(4, 4);
+        bad_mask.walls = vec![0; 3];
+        assert!(matches!(
+            find_hamiltonian_path_internal(p(0, 0), p(3, 0), &bad_mask, 100),
+            Err(SolveError::InvalidWallMask {
+                expected: 16,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_turn_objective() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(6, 6);
+        let (start, end) = (p(0, 0), p(5, 0));
+        let waypoints = vec![p(2, 3), p(4, 4)];
+        let search = |turn_objective| {
+            let options = SearchOptions {
+                max_iterations: 100_000,
+                seed: Some(9),
+                waypoints: waypoints.clone(),
+                turn_objective,
+                turn_steps: 2_000,
+                ..SearchOptions::default()
+            };
+            find_hamiltonian_path_with_options(
+                start,
+                end,
+                &grid,
+                &options,
+                &mut SystemClock::new(),
+                &mut |_| {},
+            )
+            .unwrap()
+        };
+
+        let plain = search(None);
+        assert_eq!(plain.turns, None);
+        let baseline = count_turns(&plain.path);
+
+        let twisty = search(Some(TurnObjective::Maximize));
+        assert!(twisty.found);
+        assert_eq!(twisty.turns, Some(count_turns(&twisty.path)));
+        assert!(twisty.turns.unwrap() > baseline);
+        assert_eq!((twisty.path[0], twisty.path[35]), (start, end));
+        let position_in = |path: &[Point], p: Point| path.iter().position(|&c| c == p).unwrap();
+        assert!(satisfies_constraints(
+            |p| position_in(&twisty.path, p),
+            &waypoints,
+            &[]
+        ));
+        assert!(path_to_road_grid_internal(&twisty.path, &grid, &[]).is_ok());
+
+        let straight = search(Some(TurnObjective::Minimize));
+        assert!(straight.turns.unwrap() <= baseline);
+        assert!(satisfies_constraints(
+            |p| position_in(&straight.path, p),
+            &waypoints,
+            &[]
+        ));
+    }
+
+    #[test]
+    fn test_find_options_object() {
+        let p = |row, col| Point { row, col };
+        let json = r#"{
+            "blocked": [0, 0, 0, 1],
+            "walls": [8, 4, 0, 0],
+            "seed": 7,
+            "waypoints": [{ "row": 1, "col": 0 }],
+            "fixed_edges": [{ "from": { "row": 0, "col": 0 }, "to": { "row": 1, "col": 0 } }],
+            "turn_objective": { "target": 2 },
+            "progress_interval": 100
+        }"#;
+        let options: FindOptions = serde_json::from_str(json).unwrap();
+        let (grid, options) = options.into_search(2, 2, false);
+        assert!(grid.is_blocked(1, 1) && grid.is_walled(0, 0, 3));
+        assert_eq!(options.max_iterations, DEFAULT_MAX_ITERATIONS);
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.waypoints, vec![p(1, 0)]);
+        assert_eq!(options.fixed_edges.len(), 1);
+        assert_eq!(options.turn_objective, Some(TurnObjective::Target(2)));
+        assert_eq!(options.turn_steps, DEFAULT_TURN_STEPS);
+        // No callback to report to
+        assert_eq!(options.progress_interval, None);
+
+        let (grid, options) = FindOptions::default().into_search(3, 3, true);
+        assert_eq!(grid.open_cells(), 9);
+        assert!(options.waypoints.is_empty() && options.turn_objective.is_none());
+    }
+
+    #[test]
+    fn test_road_masks_match_road_grid() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(4, 4).with_blocked(&[p(0, 3)]).unwrap();
+        let result = find_hamiltonian_path_internal(p(0, 0), p(3, 3), &grid, 10_000).unwrap();
+        assert!(result.found);
+
+        let road = path_to_road_grid_internal(&result.path, &grid, &[]).unwrap();
+        let masks = path_to_road_masks_internal(&result.path, &grid).unwrap();
+        assert_eq!((masks.rows(), masks.cols()), (4, 4));
+        for row in 0..4 {
+            for col in 0..4 {
+                let mask = masks.mask(row, col).unwrap();
+                match &road[row as usize][col as usize] {
+                    Some(cell) => {
+                        assert_eq!(mask, cell.mask);
+                        assert_eq!(masks.path_index(row, col), Some(cell.path_index as u32));
+                    }
+                    None => {
+                        assert!(mask.is_empty());
+                        assert_eq!(masks.path_index(row, col), None);
+                    }
+                }
+            }
+        }
+        assert_eq!(masks.connections().len(), 16);
+        assert_eq!(masks.path_indices()[3], OFF_ROAD);
+        assert!(masks.mask(0, 0).unwrap().is_endpoint() && masks.mask(3, 3).unwrap().is_endpoint());
+
+        // Cells off the grid, including ones whose row-major index is in range
+        for (row, col) in [(-1, 0), (0, 4), (4, 0), (1, -1)] {
+            assert_eq!(masks.mask(row, col), None);
+            assert_eq!(masks.path_index(row, col), None);
+        }
+
+        assert!(matches!(
+            path_to_road_masks_internal(&[p(0, 0), p(1, 1)], &grid),
+            Err(SolveError::InvalidPath { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_road_grid_names_previous_cell_first() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(2, 2);
+        let path = [p(0, 1), p(0, 0), p(1, 0), p(1, 1)];
+        let fixed = [Edge {
+            from: p(1, 0),
+            to: p(1, 1),
+        }];
+
+        let road = path_to_road_grid_internal(&path, &grid, &fixed).unwrap();
+        let cells = road_grid_to_js(road, &path, false);
+        let names = |row: usize, col: usize| cells[row][col].as_ref().unwrap().connections.clone();
+        assert_eq!(names(0, 1), ["left"]);
+        assert_eq!(names(0, 0), ["right", "down"]);
+        assert_eq!(names(1, 0), ["up", "right"]);
+        assert_eq!(cells[1][0].as_ref().unwrap().fixed, ["right"]);
+        assert!(cells[0][0].as_ref().unwrap().fixed.is_empty());
+
+        let road = cycle_to_road_grid_internal(&path, &grid).unwrap();
+        let cells = road_grid_to_js(road, &path, true);
+        assert_eq!(cells[0][1].as_ref().unwrap().connections, ["down", "left"]);
+        assert_eq!(cells[1][1].as_ref().unwrap().connections, ["left", "up"]);
+    }
+
+    #[test]
+    fn test_tile_grid() {
+        let p = |row, col| Point { row, col };
+        let grid = Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap();
+        let ring = [
+            p(0, 0),
+            p(0, 1),
+            p(0, 2),
+            p(1, 2),
+            p(2, 2),
+            p(2, 1),
+            p(2, 0),
+            p(1, 0),
+        ];
+        let tiles = path_to_tile_grid_internal(&ring, &grid).unwrap();
+
+        assert!(tiles[1][1].is_none());
+        let tile = |row: usize, col: usize| tiles[row][col].as_ref().unwrap();
+        assert_eq!(tile(0, 0).tile, RoadTile::StartCap);
+        assert_eq!(tile(0, 0).exit, Some(Direction::Right));
+        assert_eq!(tile(0, 1).tile, RoadTile::Straight(Axis::Horizontal));
+        assert_eq!(tile(0, 2).tile, RoadTile::Corner(Corner::SouthWest));
+        assert_eq!(tile(2, 2).tile, RoadTile::Corner(Corner::NorthWest));
+        assert_eq!(tile(2, 0).tile, RoadTile::Corner(Corner::NorthEast));
+        assert_eq!(tile(1, 0).tile, RoadTile::EndCap);
+        assert_eq!(tile(1, 0).entry, Some(Direction::Down));
+        assert_eq!(tile(1, 0).path_index, 7);
+
+        assert!(matches!(
+            path_to_tile_grid_internal(&[p(0, 0), p(1, 1)], &grid),
+            Err(SolveError::BlockedCell { .. })
+        ));
     }
 
     #[test]
@@ -691,7 +3189,7 @@ mod tests {
         ];
 
         for (start, end) in test_cases.iter().take(attempts) {
-            let result = find_hamiltonian_path_internal(*start, *end, grid_size, max_iterations);
+            let result = find_hamiltonian_path_internal(*start, *end, &grid_size.into(), max_iterations).unwrap();
             if result.found {
                 success_count += 1;
                 assert_eq!(result.path.len(), 36);
@@ -717,7 +3215,7 @@ mod tests {
         let mut success_count = 0;
         let attempts = 5;
 
-        let test_cases = vec![
+        let test_cases = [
             (Point { row: 0, col: 0 }, Point { row: 0, col: 1 }),
             (Point { row: 0, col: 0 }, Point { row: 1, col: 0 }),
             (Point { row: 0, col: 0 }, Point { row: 7, col: 0 }),
@@ -726,7 +3224,7 @@ mod tests {
         ];
 
         for (start, end) in test_cases.iter().take(attempts) {
-            let result = find_hamiltonian_path_internal(*start, *end, grid_size, max_iterations);
+            let result = find_hamiltonian_path_internal(*start, *end, &grid_size.into(), max_iterations).unwrap();
             if result.found {
                 success_count += 1;
                 assert_eq!(result.path.len(), 64);
@@ -754,7 +3252,7 @@ mod tests {
 
         eprintln!("\n10x10 Grid - Iteration Limit Impact:");
         for limit in limits {
-            let result = find_hamiltonian_path_internal(start, end, grid_size, limit);
+            let result = find_hamiltonian_path_internal(start, end, &grid_size.into(), limit).unwrap();
             eprintln!(
                 "  Limit {:>9}: {} (used {} iterations)",
                 limit,
@@ -782,10 +3280,10 @@ mod tests {
         for i in 0..test_count {
             // Generate endpoints with different parity from (0,0)
             let row = i % 10;
-            let col = if (row + 0) % 2 == 0 { 1 } else { 0 }; // Ensure different parity
+            let col = if row % 2 == 0 { 1 } else { 0 }; // Ensure different parity
             let end = Point { row, col };
 
-            let result = find_hamiltonian_path_internal(start, end, grid_size, max_iterations);
+            let result = find_hamiltonian_path_internal(start, end, &grid_size.into(), max_iterations).unwrap();
 
             if result.found {
                 found_count += 1;
@@ -818,7 +3316,7 @@ mod tests {
         let start = Point { row: 0, col: 0 };
         let end = Point { row: 4, col: 4 }; // Same parity for odd grid
 
-        let result = find_hamiltonian_path_internal(start, end, grid_size, 500_000);
+        let result = find_hamiltonian_path_internal(start, end, &grid_size.into(), 500_000).unwrap();
 
         if result.found {
             // Check path length
@@ -842,6 +3340,21 @@ mod tests {
                 let dc = (curr.col - prev.col).abs();
                 assert_eq!(dr + dc, 1, "Non-adjacent cells in path");
             }
+
+            // The verifier must agree
+            let constraints = PathConstraints {
+                start: Some(start),
+                end: Some(end),
+                ..PathConstraints::default()
+            };
+            let violations =
+                verify_hamiltonian_path(&result.path, &grid_size.into(), &constraints).unwrap();
+            assert!(
+                violations.is_empty(),
+                "{:?} in path:\n{}",
+                violations,
+                RoadGrid::new(&result.path, &grid_size.into())
+            );
         }
     }
 }
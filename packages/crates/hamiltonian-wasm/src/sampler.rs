@@ -0,0 +1,303 @@
+//! Random Hamiltonian paths via the backbite Markov chain
+//!
+//! A backbite move picks a grid neighbor of one path end, links the end to it
+//! and drops the path edge that would otherwise close a loop; the section in
+//! between is reversed and a new cell becomes the end. Proposing each of the
+//! four directions with equal probability (off-grid, blocked and walled
+//! proposals are no-ops) makes the chain symmetric, so repeated moves drift
+//! towards a uniform distribution over Hamiltonian paths instead of the
+//! edge-hugging roads the heuristic search prefers.
+
+use crate::grid::Grid;
+use crate::rng::Rng;
+use crate::turns::{count_turns, is_turn};
+use crate::{Point, DIRECTIONS};
+
+/// Which path ends the sampler is allowed to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FixedEnds {
+    pub start: bool,
+    pub end: bool,
+}
+
+/// Hamiltonian path with an index from cell to path position and a running
+/// count of its turns
+pub(crate) struct Backbite<'a> {
+    grid: &'a Grid,
+    path: Vec<Point>,
+    position: Vec<usize>,
+    turns: u32,
+}
+
+impl<'a> Backbite<'a> {
+    pub(crate) fn new(grid: &'a Grid, path: Vec<Point>) -> Self {
+        let mut position = vec![0; (grid.rows * grid.cols) as usize];
+        for (i, p) in path.iter().enumerate() {
+            position[(p.row * grid.cols + p.col) as usize] = i;
+        }
+        Self {
+            grid,
+            turns: count_turns(&path),
+            path,
+            position,
+        }
+    }
+
+    /// Index of an open cell along the path
+    pub(crate) fn position(&self, p: Point) -> usize {
+        self.position[(p.row * self.grid.cols + p.col) as usize]
+    }
+
+    /// Same as `count_turns(self.path())`, without walking the path
+    pub(crate) fn turns(&self) -> u32 {
+        self.turns
+    }
+
+    pub(crate) fn into_path(self) -> Vec<Point> {
+        self.path
+    }
+
+    /// Reverse `path[lo..hi]`, keeping the position index and turn count in
+    /// sync. Cells inside the range keep their pair of neighbors, so only the
+    /// turns at the two cut points can change.
+    fn reverse(&mut self, lo: usize, hi: usize) {
+        let cut_points = [lo.wrapping_sub(1), lo, hi - 1, hi];
+        let before = self.turns_at(&cut_points);
+        self.path[lo..hi].reverse();
+        for i in lo..hi {
+            let p = self.path[i];
+            self.position[(p.row * self.grid.cols + p.col) as usize] = i;
+        }
+        self.turns = self.turns - before + self.turns_at(&cut_points);
+    }
+
+    /// Turns at the distinct path indices in `indices`; indices of the ends
+    /// or past them never turn
+    fn turns_at(&self, indices: &[usize]) -> u32 {
+        let inner = 1..self.path.len().saturating_sub(1);
+        let mut turns = 0;
+        for (k, &i) in indices.iter().enumerate() {
+            if inner.contains(&i)
+                && !indices[..k].contains(&i)
+                && is_turn(self.path[i - 1], self.path[i], self.path[i + 1])
+            {
+                turns += 1;
+            }
+        }
+        turns
+    }
+
+    /// Pick a random direction for the chosen end and apply the move.
+    ///
+    /// Returns the reversed range so the move can be undone, or `None` when
+    /// the proposal was a no-op.
+    fn backbite(&mut self, at_start: bool, rng: &mut Rng) -> Option<(usize, usize)> {
+        let last = self.path.len() - 1;
+        let end = if at_start {
+            self.path[0]
+        } else {
+            self.path[last]
+        };
+        let direction = rng.below(DIRECTIONS.len());
+        let (dr, dc, _) = DIRECTIONS[direction];
+        let (row, col) = (end.row + dr, end.col + dc);
+        if !self.grid.can_step(end.row, end.col, direction) {
+            return None;
+        }
+
+        let i = self.position[(row * self.grid.cols + col) as usize];
+        let range = if at_start {
+            // Link path[0]-path[i], drop path[i - 1]-path[i]
+            (i > 1).then_some((0, i))
+        } else {
+            // Link path[last]-path[i], drop path[i]-path[i + 1]
+            (i + 1 < last).then_some((i + 1, last + 1))
+        }?;
+        self.reverse(range.0, range.1);
+        Some(range)
+    }
+
+    /// Move one end away and back again, keeping the result only if the end
+    /// returns to its cell within `max_moves` moves.
+    ///
+    /// Returns the kept moves, which `undo` reverts.
+    pub(crate) fn excursion(
+        &mut self,
+        at_start: bool,
+        max_moves: usize,
+        rng: &mut Rng,
+    ) -> Vec<(usize, usize)> {
+        let end = |path: &[Point]| {
+            if at_start {
+                path[0]
+            } else {
+                path[path.len() - 1]
+            }
+        };
+        let home = end(&self.path);
+        let mut moves = Vec::new();
+        for _ in 0..max_moves {
+            if let Some(range) = self.backbite(at_start, rng) {
+                moves.push(range);
+            }
+            if end(&self.path) == home {
+                return moves;
+            }
+        }
+        self.undo(&moves);
+        Vec::new()
+    }
+
+    /// Revert moves returned by `backbite` or `excursion`, latest last
+    pub(crate) fn undo(&mut self, moves: &[(usize, usize)]) {
+        for &(lo, hi) in moves.iter().rev() {
+            self.reverse(lo, hi);
+        }
+    }
+}
+
+/// Apply `steps` backbite moves to a Hamiltonian path of `grid`.
+///
+/// Moves only touch the ends that are not fixed. With both ends fixed each
+/// step is an excursion of the end that is kept only if it comes back, so
+/// the interior still mixes while both endpoints stay put; in that mode the
+/// distribution is an approximation of uniform.
+pub(crate) fn sample_path(
+    path: Vec<Point>,
+    grid: &Grid,
+    steps: u32,
+    fixed: FixedEnds,
+    rng: &mut Rng,
+) -> Vec<Point> {
+    if path.len() < 3 {
+        return path;
+    }
+
+    let max_excursion = path.len();
+    let mut chain = Backbite::new(grid, path);
+    for _ in 0..steps {
+        match (fixed.start, fixed.end) {
+            (false, false) => {
+                let at_start = rng.below(2) == 0;
+                chain.backbite(at_start, rng);
+            }
+            (true, false) => {
+                chain.backbite(false, rng);
+            }
+            (false, true) => {
+                chain.backbite(true, rng);
+            }
+            (true, true) => {
+                chain.excursion(false, max_excursion, rng);
+            }
+        }
+    }
+    chain.path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn snake(rows: i32, cols: i32) -> Vec<Point> {
+        (0..rows)
+            .flat_map(|row| {
+                let cols: Vec<i32> = if row % 2 == 0 {
+                    (0..cols).collect()
+                } else {
+                    (0..cols).rev().collect()
+                };
+                cols.into_iter().map(move |col| Point { row, col })
+            })
+            .collect()
+    }
+
+    fn assert_hamiltonian(path: &[Point], grid: &Grid) {
+        assert_eq!(path.len(), grid.open_cells());
+        let unique: HashSet<Point> = path.iter().copied().collect();
+        assert_eq!(unique.len(), path.len(), "Duplicate cell in path");
+        assert!(path.iter().all(|p| grid.is_open(p.row, p.col)));
+        for w in path.windows(2) {
+            let d = (w[0].row - w[1].row).abs() + (w[0].col - w[1].col).abs();
+            assert_eq!(d, 1, "Non-adjacent cells in path");
+        }
+    }
+
+    #[test]
+    fn test_free_ends_stay_hamiltonian_and_vary() {
+        let grid = Grid::new(6, 7);
+        let free = FixedEnds {
+            start: false,
+            end: false,
+        };
+        let mut rng = Rng::new(3);
+        let mut seen = HashSet::new();
+        for _ in 0..10 {
+            let path = sample_path(snake(6, 7), &grid, 2_000, free, &mut rng);
+            assert_hamiltonian(&path, &grid);
+            seen.insert(path);
+        }
+        assert!(seen.len() > 1);
+    }
+
+    #[test]
+    fn test_fixed_ends_are_kept() {
+        let grid = Grid::new(5, 6);
+        let initial = snake(5, 6);
+        let (first, last) = (initial[0], initial[initial.len() - 1]);
+
+        for (start, end) in [(true, false), (false, true), (true, true)] {
+            let fixed = FixedEnds { start, end };
+            let path = sample_path(initial.clone(), &grid, 500, fixed, &mut Rng::new(11));
+            assert_hamiltonian(&path, &grid);
+            if start {
+                assert_eq!(path[0], first);
+            }
+            if end {
+                assert_eq!(path[path.len() - 1], last);
+            }
+            assert_ne!(path, initial, "sampler should move away from the snake");
+        }
+    }
+
+    #[test]
+    fn test_turn_count_follows_moves() {
+        let grid = Grid::new(5, 6);
+        let mut chain = Backbite::new(&grid, snake(5, 6));
+        let mut rng = Rng::new(8);
+        for step in 0..500 {
+            let moves = chain.excursion(step % 2 == 0, 30, &mut rng);
+            assert_eq!(chain.turns(), count_turns(&chain.path));
+            if step % 3 == 0 {
+                chain.undo(&moves);
+                assert_eq!(chain.turns(), count_turns(&chain.path));
+            }
+        }
+        for p in &chain.path {
+            assert_eq!(chain.path[chain.position(*p)], *p);
+        }
+    }
+
+    #[test]
+    fn test_obstacles_are_respected() {
+        let p = |row, col| Point { row, col };
+        // Snake through the rows above, then the open half of the last row
+        let grid = Grid::new(4, 4).with_blocked(&[p(3, 0), p(3, 1)]).unwrap();
+        let path: Vec<Point> = snake(3, 4)
+            .into_iter()
+            .chain([p(3, 2), p(3, 3)].into_iter().rev())
+            .collect();
+        let path = sample_path(
+            path,
+            &grid,
+            1_000,
+            FixedEnds {
+                start: false,
+                end: false,
+            },
+            &mut Rng::new(5),
+        );
+        assert_hamiltonian(&path, &grid);
+    }
+}
@@ -0,0 +1,774 @@
+//! Resumable Hamiltonian path search
+//!
+//! The depth-first search behind the path finder, driven by an explicit frame
+//! stack instead of recursion. All state lives in `Search`, so the search can
+//! be advanced a bounded number of steps at a time, interleaved with other
+//! work, abandoned at any point or serialized and resumed later. Depth is
+//! limited by heap memory rather than the (small) WASM call stack.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::grid::{self, Grid};
+use crate::rng::Rng;
+use crate::{
+    fixed_edge_masks, get_unvisited_neighbors, is_in_bounds, is_likely_articulation_point,
+    is_remaining_connected, sort_by_warnsdorff, Edge, Feasibility, GridSize, InfeasibleReason,
+    PathResult, Point, VisitedBitset, DIRECTIONS,
+};
+
+/// Where a stepped search stands after a call to `step`
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SolverStatus {
+    /// Budget used up; call `step` again to continue
+    Running,
+    /// A Hamiltonian path was found
+    Found,
+    /// Every branch was explored without finding a path
+    Exhausted,
+    /// Stopped by `cancel`
+    Cancelled,
+}
+
+/// Why a search stopped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Termination {
+    /// A Hamiltonian path was found
+    Found,
+    /// Every branch was explored
+    Exhausted,
+    /// The iteration budget ran out
+    IterationLimit,
+    /// The wall-clock budget ran out
+    Deadline,
+    /// The caller cancelled the search
+    Cancelled,
+}
+
+/// Counters describing the work a search has done
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Cells entered, including the start cell
+    pub iterations: u32,
+    /// Moves made: cells entered after the start plus moves rejected by a
+    /// connectivity or waypoint check. `step` budgets are counted in moves.
+    pub moves: u32,
+    /// Full connectivity checks of the unvisited region
+    pub connectivity_checks: u32,
+    /// Moves rejected because they would split the unvisited region
+    pub connectivity_prunes: u32,
+    /// Cells entered with no way forward: stuck, or at the end too early
+    pub dead_end_prunes: u32,
+    /// Longest partial path reached, in cells
+    pub max_depth: u32,
+    /// Moves rejected because they skip a waypoint or cut the next one off
+    pub waypoint_prunes: u32,
+    /// Most waypoints visited (in order) by any partial path
+    pub waypoints_reached: u32,
+    /// Index of the waypoint behind the latest waypoint prune: the one a
+    /// move would have skipped or cut off from the path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned_waypoint: Option<u32>,
+    /// Moves rejected because they leave out or contradict a fixed edge
+    pub fixed_edge_prunes: u32,
+}
+
+/// Best-effort road when no Hamiltonian path was found
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PartialPath {
+    /// Simple path from the start: the longest one seen, or on a tie one
+    /// that reached the end
+    pub path: Vec<Point>,
+    /// Whether `path` ends at the requested end cell
+    pub reaches_end: bool,
+    /// Open cells the path does not cover, in row-major order
+    pub uncovered: Vec<Point>,
+}
+
+/// Longest path seen so far, kept in step with the search path: only the
+/// cells pushed since the two diverged are copied when the record is beaten
+#[derive(Default, Serialize, Deserialize)]
+struct Record {
+    path: Vec<Point>,
+    /// Length of the prefix `path` shares with the search path
+    shared: usize,
+}
+
+impl Record {
+    /// Take `current` as the new record if it is longer
+    fn offer(&mut self, current: &[Point]) {
+        if current.len() > self.path.len() {
+            self.path.truncate(self.shared);
+            self.path.extend_from_slice(&current[self.shared..]);
+            self.shared = current.len();
+        }
+    }
+
+    /// The search path was cut back to `len` cells
+    fn retreat(&mut self, len: usize) {
+        self.shared = self.shared.min(len);
+    }
+}
+
+/// Neighbors of one path cell, in the order they are tried
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    neighbors: Vec<(i32, i32)>,
+    next: usize,
+}
+
+/// Search state for one start/end request
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Search {
+    grid_size: GridSize,
+    /// Wall mask of the grid (see `Grid::walls`)
+    walls: Vec<u8>,
+    /// `None` accepts paths ending anywhere
+    end: Option<Point>,
+    blocked: VisitedBitset,
+    visited: VisitedBitset,
+    scratch: VisitedBitset,
+    path: Vec<Point>,
+    /// Longest path seen so far
+    longest: Record,
+    /// Longest path seen that ended at `end` (too early)
+    longest_to_end: Record,
+    frames: Vec<Frame>,
+    unvisited_count: usize,
+    /// Cells the path must pass through, in order
+    waypoints: Vec<Point>,
+    /// Waypoints visited by the current path
+    next_waypoint: usize,
+    /// Fixed edges of each cell as a bitmask over `DIRECTIONS`; empty
+    /// without fixed edges
+    fixed: Vec<u8>,
+    stats: SearchStats,
+    rng: Option<Rng>,
+    status: SolverStatus,
+    /// Reported instead of `Exhausted` when the request was ruled out upfront
+    obstruction: Option<InfeasibleReason>,
+}
+
+impl Search {
+    /// Endpoints must be distinct open cells of `grid`
+    pub(crate) fn new(start: Point, end: Point, grid: &Grid, seed: Option<u64>) -> Self {
+        Self::starting(start, Some(end), grid, seed)
+    }
+
+    /// Search for paths from `start` that end on any cell. Neighbors are
+    /// tried in `DIRECTIONS` order, as there is no end to steer towards.
+    pub(crate) fn from_start(start: Point, grid: &Grid) -> Self {
+        Self::starting(start, None, grid, None)
+    }
+
+    fn starting(start: Point, end: Option<Point>, grid: &Grid, seed: Option<u64>) -> Self {
+        let blocked = VisitedBitset::from_blocked(grid);
+        let mut visited = blocked.clone();
+        visited.set(start.row, start.col);
+        let mut search = Self {
+            grid_size: grid.size(),
+            walls: grid.walls.clone(),
+            end,
+            blocked,
+            visited,
+            scratch: VisitedBitset::new(&grid.size()),
+            path: vec![start],
+            longest: Record::default(),
+            longest_to_end: Record::default(),
+            frames: Vec::new(),
+            unvisited_count: grid.open_cells() - 1,
+            waypoints: Vec::new(),
+            next_waypoint: 0,
+            fixed: Vec::new(),
+            stats: SearchStats::default(),
+            rng: seed.map(Rng::new),
+            status: SolverStatus::Running,
+            obstruction: None,
+        };
+        search.enter();
+        search
+    }
+
+    /// A search that is already over because no path can exist
+    pub(crate) fn ruled_out(grid: &Grid, reason: InfeasibleReason) -> Self {
+        Self {
+            grid_size: grid.size(),
+            walls: Vec::new(),
+            end: None,
+            blocked: VisitedBitset::new(&grid.size()),
+            visited: VisitedBitset::new(&grid.size()),
+            scratch: VisitedBitset::new(&grid.size()),
+            path: Vec::new(),
+            longest: Record::default(),
+            longest_to_end: Record::default(),
+            frames: Vec::new(),
+            unvisited_count: 0,
+            waypoints: Vec::new(),
+            next_waypoint: 0,
+            fixed: Vec::new(),
+            stats: SearchStats::default(),
+            rng: None,
+            status: SolverStatus::Exhausted,
+            obstruction: Some(reason),
+        }
+    }
+
+    /// Require the path to pass through `waypoints` in order. They must be
+    /// distinct open cells other than the endpoints.
+    ///
+    /// A waypoint that cannot be reached from the start without passing the
+    /// end or a later waypoint rules the request out before any search.
+    pub(crate) fn with_waypoints(mut self, waypoints: Vec<Point>) -> Self {
+        self.waypoints = waypoints;
+        if self.status == SolverStatus::Running {
+            let start = self.path[0];
+            if let Some(index) =
+                (0..self.waypoints.len()).find(|&i| !self.waypoint_reachable(start, i))
+            {
+                self.status = SolverStatus::Exhausted;
+                self.obstruction = Some(InfeasibleReason::UnreachableWaypoint { index });
+            }
+        }
+        self
+    }
+
+    /// Require the path to use every edge in `edges`. They must join adjacent
+    /// open cells and be able to lie on one path from start to end.
+    pub(crate) fn with_fixed_edges(mut self, edges: &[Edge]) -> Self {
+        if !edges.is_empty() {
+            self.fixed = fixed_edge_masks(edges, &self.grid_size);
+        }
+        self
+    }
+
+    pub(crate) fn status(&self) -> SolverStatus {
+        self.status
+    }
+
+    /// Cells entered so far
+    pub(crate) fn iterations(&self) -> u32 {
+        self.stats.iterations
+    }
+
+    pub(crate) fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// The path so far; a Hamiltonian path once the status is `Found`
+    pub(crate) fn path(&self) -> &[Point] {
+        &self.path
+    }
+
+    /// After `Found`, go on looking for the next path with the next `step`
+    pub(crate) fn resume(&mut self) {
+        if self.status == SolverStatus::Found {
+            self.status = SolverStatus::Running;
+        }
+    }
+
+    /// Stop the search; later `step` calls do nothing
+    pub(crate) fn cancel(&mut self) {
+        if self.status == SolverStatus::Running {
+            self.status = SolverStatus::Cancelled;
+        }
+    }
+
+    /// Account for the last cell of the path and queue its neighbors
+    fn enter(&mut self) {
+        self.stats.iterations = self.stats.iterations.saturating_add(1);
+        self.stats.max_depth = self.stats.max_depth.max(self.path.len() as u32);
+        let current = *self.path.last().unwrap();
+
+        let at_end = self.end.is_none_or(|end| current == end);
+
+        // Success: visited all cells and reached endpoint. The empty frame
+        // lets `resume` backtrack from here.
+        if self.unvisited_count == 0 && at_end {
+            self.status = SolverStatus::Found;
+            self.frames.push(Frame {
+                neighbors: Vec::new(),
+                next: 0,
+            });
+            return;
+        }
+
+        self.longest.offer(&self.path);
+        if self.end == Some(current) {
+            self.longest_to_end.offer(&self.path);
+        }
+
+        // A full path ending elsewhere, or the endpoint reached too early,
+        // leaves nothing to try from here
+        let mut neighbors = Vec::new();
+        if self.unvisited_count > 0 && self.end != Some(current) {
+            neighbors = get_unvisited_neighbors(
+                current.row,
+                current.col,
+                &self.grid_size,
+                &self.walls,
+                &self.visited,
+            );
+            if let Some(end) = &self.end {
+                sort_by_warnsdorff(
+                    &mut neighbors,
+                    end,
+                    &self.grid_size,
+                    &self.walls,
+                    &self.visited,
+                    self.unvisited_count,
+                    self.rng.as_mut(),
+                );
+            }
+        }
+        if neighbors.is_empty() {
+            self.stats.dead_end_prunes += 1;
+        }
+        let neighbors = neighbors.into_iter().map(|(nr, nc, _)| (nr, nc)).collect();
+        self.frames.push(Frame { neighbors, next: 0 });
+    }
+
+    /// Advance the search by at most `budget` moves.
+    ///
+    /// A move enters a cell or is rejected by the connectivity or waypoint
+    /// check, so each one costs at most two scans of the grid. Backtracking
+    /// and the constant-time prunes are free; they are bounded by the moves
+    /// made and the length of the path, which keeps every call bounded.
+    pub(crate) fn step(&mut self, budget: u32) -> SolverStatus {
+        let mut remaining = budget;
+        while self.status == SolverStatus::Running && remaining > 0 {
+            let Some(frame) = self.frames.last_mut() else {
+                self.status = SolverStatus::Exhausted;
+                break;
+            };
+
+            let Some(&(nr, nc)) = frame.neighbors.get(frame.next) else {
+                // Every neighbor tried: backtrack
+                self.frames.pop();
+                let cell = self.path.pop().unwrap();
+                self.longest.retreat(self.path.len());
+                self.longest_to_end.retreat(self.path.len());
+                if !self.path.is_empty() {
+                    self.visited.clear(cell.row, cell.col);
+                    self.unvisited_count += 1;
+                }
+                if self.next_waypoint > 0 && self.waypoints[self.next_waypoint - 1] == cell {
+                    self.next_waypoint -= 1;
+                }
+                continue;
+            };
+            frame.next += 1;
+
+            // Waypoints are entered in order, and the next one must stay
+            // reachable without crossing a later one
+            let cell = Point { row: nr, col: nc };
+            let mut next_waypoint = self.next_waypoint;
+            if let Some(index) = self.waypoints.iter().position(|&w| w == cell) {
+                if index != next_waypoint {
+                    self.stats.waypoint_prunes += 1;
+                    self.stats.pruned_waypoint = Some(next_waypoint as u32);
+                    continue;
+                }
+                next_waypoint += 1;
+            }
+
+            if !self.fixed_edges_allow(cell) {
+                self.stats.fixed_edge_prunes += 1;
+                continue;
+            }
+
+            // Charged before the checks below, which may scan the whole grid
+            remaining -= 1;
+            self.stats.moves = self.stats.moves.saturating_add(1);
+
+            let is_endpoint = self.end == Some(cell);
+            self.visited.set(nr, nc);
+            let new_unvisited = self.unvisited_count - 1;
+
+            // Pruning: check connectivity only when necessary
+            // Skip check if moving to endpoint (it doesn't need further connections)
+            // Also skip if only 1-2 cells remain (always connected or trivially checkable)
+            let should_check_connectivity = !is_endpoint
+                && new_unvisited > 2
+                && is_likely_articulation_point(
+                    nr,
+                    nc,
+                    &self.grid_size,
+                    &self.walls,
+                    &self.visited,
+                );
+
+            if should_check_connectivity {
+                self.stats.connectivity_checks += 1;
+                if !is_remaining_connected(
+                    &self.grid_size,
+                    &self.walls,
+                    &self.visited,
+                    new_unvisited,
+                    &mut self.scratch,
+                ) {
+                    self.stats.connectivity_prunes += 1;
+                    self.visited.clear(nr, nc);
+                    continue;
+                }
+            }
+
+            if next_waypoint < self.waypoints.len() && !self.waypoint_reachable(cell, next_waypoint)
+            {
+                self.stats.waypoint_prunes += 1;
+                self.stats.pruned_waypoint = Some(next_waypoint as u32);
+                self.visited.clear(nr, nc);
+                continue;
+            }
+
+            self.path.push(cell);
+            self.unvisited_count = new_unvisited;
+            self.next_waypoint = next_waypoint;
+            self.stats.waypoints_reached = self.stats.waypoints_reached.max(next_waypoint as u32);
+            self.enter();
+        }
+        self.status
+    }
+
+    /// Cells joined to `cell` by a fixed edge
+    fn fixed_partners(&self, cell: Point) -> impl Iterator<Item = Point> {
+        let index = (cell.row * self.grid_size.cols + cell.col) as usize;
+        let mask = self.fixed.get(index).copied().unwrap_or(0);
+        DIRECTIONS
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| mask & (1 << i) != 0)
+            .map(move |(_, &(dr, dc, _))| Point {
+                row: cell.row + dr,
+                col: cell.col + dc,
+            })
+    }
+
+    /// Whether stepping from the end of the path to `next` keeps every fixed
+    /// edge usable. A cell with two fixed edges is fully determined; with one
+    /// it leaves either by it or, if it was entered by it, freely.
+    fn fixed_edges_allow(&self, next: Point) -> bool {
+        if self.fixed.is_empty() {
+            return true;
+        }
+        let current = self.path[self.path.len() - 1];
+        let prev = self.path.len().checked_sub(2).map(|i| self.path[i]);
+
+        // A fixed edge out of the current cell has to be the next step
+        if self
+            .fixed_partners(current)
+            .any(|partner| Some(partner) != prev && partner != next)
+        {
+            return false;
+        }
+
+        // `next` is entered from `current`, so any other fixed edge of it
+        // has to be its way out
+        let mut exits = self.fixed_partners(next).filter(|&p| p != current);
+        match exits.next() {
+            None => true,
+            Some(exit) => {
+                self.end != Some(next)
+                    && exits.next().is_none()
+                    && !self.visited.get(exit.row, exit.col)
+            }
+        }
+    }
+
+    /// Whether waypoint `index` can be reached from `from` through unvisited
+    /// cells without passing the end or a later waypoint
+    fn waypoint_reachable(&mut self, from: Point, index: usize) -> bool {
+        let target = self.waypoints[index];
+        let later = &self.waypoints[index + 1..];
+        self.scratch.reset();
+        self.scratch.set(from.row, from.col);
+        let mut stack = vec![from];
+        while let Some(p) = stack.pop() {
+            for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
+                let next = Point {
+                    row: p.row + dr,
+                    col: p.col + dc,
+                };
+                if !is_in_bounds(next.row, next.col, &self.grid_size)
+                    || grid::is_walled(&self.walls, p.row, p.col, direction, &self.grid_size)
+                {
+                    continue;
+                }
+                if next == target {
+                    return true;
+                }
+                if !self.visited.get(next.row, next.col)
+                    && !self.scratch.get(next.row, next.col)
+                    && self.end != Some(next)
+                    && !later.contains(&next)
+                {
+                    self.scratch.set(next.row, next.col);
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Result so far: the path once found, otherwise the current verdict
+    pub(crate) fn result(&self) -> PathResult {
+        let (found, verdict, termination) = match self.status {
+            SolverStatus::Found => (true, Feasibility::Possible, Some(Termination::Found)),
+            // Ruled out upfront: no search ran
+            SolverStatus::Exhausted => match self.obstruction {
+                Some(reason) => (false, Feasibility::Impossible(reason), None),
+                None => (
+                    false,
+                    Feasibility::Impossible(InfeasibleReason::Exhausted),
+                    Some(Termination::Exhausted),
+                ),
+            },
+            SolverStatus::Running => (false, Feasibility::Unknown, None),
+            SolverStatus::Cancelled => (false, Feasibility::Unknown, Some(Termination::Cancelled)),
+        };
+        PathResult {
+            found,
+            path: if found { self.path.clone() } else { vec![] },
+            iterations: self.stats.iterations,
+            verdict,
+            termination,
+            stats: self.obstruction.is_none().then_some(self.stats),
+            partial: (!found && self.obstruction.is_none()).then(|| self.partial()),
+            turns: None,
+        }
+    }
+
+    /// Longest road found so far, preferring one that reaches the end when
+    /// it is just as long
+    fn partial(&self) -> PartialPath {
+        let to_end = &self.longest_to_end.path;
+        let (path, reaches_end) = if !to_end.is_empty() && to_end.len() >= self.longest.path.len() {
+            (to_end, true)
+        } else {
+            (&self.longest.path, false)
+        };
+
+        let mut covered = self.blocked.clone();
+        for p in path {
+            covered.set(p.row, p.col);
+        }
+        let uncovered = (0..self.grid_size.rows)
+            .flat_map(|row| (0..self.grid_size.cols).map(move |col| Point { row, col }))
+            .filter(|p| !covered.get(p.row, p.col))
+            .collect();
+
+        PartialPath {
+            path: path.clone(),
+            reaches_end,
+            uncovered,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    #[test]
+    fn test_stepping_reaches_a_path() {
+        let grid = Grid::new(6, 6);
+        let mut search = Search::new(p(0, 0), p(5, 0), &grid, None);
+        let mut calls = 0;
+        while search.step(5) == SolverStatus::Running {
+            calls += 1;
+        }
+        assert_eq!(search.status(), SolverStatus::Found);
+        assert!(calls > 0, "a 36 cell path takes more than one 5 step slice");
+
+        let result = search.result();
+        let stats = result.stats.unwrap();
+        assert_eq!(stats.iterations, result.iterations);
+        assert_eq!(stats.max_depth, 36);
+        assert!(stats.connectivity_prunes <= stats.connectivity_checks);
+        assert!(result.found);
+        assert_eq!(result.path.len(), 36);
+        assert_eq!(result.path[35], p(5, 0));
+    }
+
+    #[test]
+    fn test_prunes_are_charged_to_the_budget() {
+        // A ring around a blocked core rejects many moves by connectivity
+        let grid = Grid::new(7, 7)
+            .with_blocked(&[
+                p(2, 2),
+                p(2, 3),
+                p(2, 4),
+                p(3, 2),
+                p(3, 4),
+                p(4, 2),
+                p(4, 3),
+            ])
+            .unwrap();
+        let mut search = Search::new(p(0, 0), p(6, 6), &grid, None);
+        let mut before = search.stats();
+        while search.step(1) == SolverStatus::Running {
+            let after = search.stats();
+            let entered = after.iterations - before.iterations;
+            let checked = after.connectivity_checks - before.connectivity_checks;
+            let pruned = after.connectivity_prunes - before.connectivity_prunes;
+            assert!(
+                entered + pruned <= 1 && checked <= 1,
+                "{:?} -> {:?}",
+                before,
+                after
+            );
+            assert_eq!(after.moves, before.moves + 1);
+            before = after;
+        }
+        assert!(search.stats().connectivity_prunes > 0);
+    }
+
+    #[test]
+    fn test_serialized_state_resumes() {
+        let grid = Grid::new(7, 7);
+        let mut whole = Search::new(p(0, 0), p(6, 6), &grid, Some(4));
+        whole.step(u32::MAX);
+
+        let mut first = Search::new(p(0, 0), p(6, 6), &grid, Some(4));
+        first.step(20);
+        let saved = serde_json::to_string(&first).unwrap();
+        let mut resumed: Search = serde_json::from_str(&saved).unwrap();
+        resumed.step(u32::MAX);
+
+        assert_eq!(resumed.status(), SolverStatus::Found);
+        assert_eq!(resumed.result().path, whole.result().path);
+        assert_eq!(resumed.stats(), whole.stats());
+    }
+
+    #[test]
+    fn test_partial_path_when_out_of_budget() {
+        let grid = Grid::new(6, 6);
+        let mut search = Search::new(p(0, 0), p(5, 0), &grid, None);
+        search.step(20);
+        let partial = search.result().partial.unwrap();
+        assert_eq!(partial.path[0], p(0, 0));
+        assert_eq!(partial.path.len() as u32, search.stats().max_depth);
+        assert_eq!(partial.path.len() + partial.uncovered.len(), 36);
+        assert!(partial
+            .path
+            .windows(2)
+            .all(|w| { (w[0].row - w[1].row).abs() + (w[0].col - w[1].col).abs() == 1 }));
+        assert!(partial.uncovered.iter().all(|c| !partial.path.contains(c)));
+    }
+
+    #[test]
+    fn test_partial_path_prefers_the_longer_road() {
+        // (2, 1) and (1, 2) are dead ends, so no path exists; the end is next
+        // to the start and reached early, but roads that miss it go further
+        let grid = Grid::new(3, 3).with_blocked(&[p(1, 1), p(2, 2)]).unwrap();
+        let mut search = Search::new(p(0, 0), p(0, 1), &grid, None);
+        assert_eq!(search.step(u32::MAX), SolverStatus::Exhausted);
+        let partial = search.result().partial.unwrap();
+        assert!(!partial.reaches_end);
+        assert_eq!(partial.path.len() as u32, search.stats().max_depth);
+        assert!(partial.path.len() > 2);
+    }
+
+    #[test]
+    fn test_waypoints_are_visited_in_order() {
+        let grid = Grid::new(5, 5);
+        let waypoints = vec![p(2, 2), p(0, 4), p(4, 4)];
+        let mut search =
+            Search::new(p(0, 0), p(4, 0), &grid, None).with_waypoints(waypoints.clone());
+        assert_eq!(search.step(u32::MAX), SolverStatus::Found);
+
+        let path = search.result().path;
+        let positions: Vec<usize> = waypoints
+            .iter()
+            .map(|w| path.iter().position(|p| p == w).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(search.stats().waypoints_reached, 3);
+    }
+
+    #[test]
+    fn test_unreachable_waypoint_is_reported() {
+        // (0, 3) is cut off from the start by the end and the later waypoints
+        let grid = Grid::new(2, 4);
+        let waypoints = vec![p(0, 3), p(0, 1), p(1, 1)];
+        let mut search = Search::new(p(0, 0), p(1, 0), &grid, None).with_waypoints(waypoints);
+        assert_eq!(search.step(u32::MAX), SolverStatus::Exhausted);
+        assert_eq!(search.iterations(), 1);
+        assert_eq!(
+            search.result().verdict,
+            Feasibility::Impossible(InfeasibleReason::UnreachableWaypoint { index: 0 })
+        );
+
+        // Every road from (0, 0) to (2, 0) passes (0, 1) before (2, 2), but
+        // (2, 2) is reachable on its own, so only the search can tell
+        let grid = Grid::new(3, 3);
+        let waypoints = vec![p(2, 2), p(0, 1)];
+        let mut search = Search::new(p(0, 0), p(2, 0), &grid, None).with_waypoints(waypoints);
+        assert_eq!(search.step(u32::MAX), SolverStatus::Exhausted);
+        assert_eq!(search.stats().waypoints_reached, 0);
+        assert_eq!(search.stats().pruned_waypoint, Some(0));
+        assert_eq!(
+            search.result().verdict,
+            Feasibility::Impossible(InfeasibleReason::Exhausted)
+        );
+    }
+
+    #[test]
+    fn test_fixed_edges_are_used() {
+        let grid = Grid::new(5, 5);
+        let edges = [
+            Edge {
+                from: p(2, 1),
+                to: p(2, 2),
+            },
+            Edge {
+                from: p(2, 2),
+                to: p(2, 3),
+            },
+            Edge {
+                from: p(0, 0),
+                to: p(1, 0),
+            },
+        ];
+        let mut search = Search::new(p(0, 0), p(4, 4), &grid, None).with_fixed_edges(&edges);
+        assert_eq!(search.step(u32::MAX), SolverStatus::Found);
+
+        let path = search.result().path;
+        for edge in &edges {
+            let a = path.iter().position(|&c| c == edge.from).unwrap();
+            let b = path.iter().position(|&c| c == edge.to).unwrap();
+            assert_eq!(a.abs_diff(b), 1, "{:?} not on {:?}", edge, path);
+        }
+        assert!(search.stats().fixed_edge_prunes > 0);
+    }
+
+    #[test]
+    fn test_exhausted_and_cancelled() {
+        // Corners of a 3x3 ring share a color, so no branch reaches the end
+        let grid = Grid::new(3, 3).with_blocked(&[p(1, 1)]).unwrap();
+        let mut search = Search::new(p(0, 0), p(0, 2), &grid, None);
+        assert_eq!(search.step(u32::MAX), SolverStatus::Exhausted);
+        assert!(search.stats().dead_end_prunes > 0);
+        // The best road ends at (0, 2) but misses the cells behind it
+        let partial = search.result().partial.unwrap();
+        assert!(partial.reaches_end);
+        assert_eq!(partial.path[partial.path.len() - 1], p(0, 2));
+        assert!(!partial.uncovered.is_empty());
+        assert_eq!(
+            search.result().verdict,
+            Feasibility::Impossible(InfeasibleReason::Exhausted)
+        );
+
+        let mut search = Search::new(p(0, 0), p(5, 0), &Grid::new(6, 6), None);
+        search.step(1);
+        search.cancel();
+        assert_eq!(search.step(1_000), SolverStatus::Cancelled);
+        assert_eq!(search.result().verdict, Feasibility::Unknown);
+        assert_eq!(search.result().termination, Some(Termination::Cancelled));
+    }
+}
@@ -0,0 +1,327 @@
+//! Standalone SVG rendering of a road
+//!
+//! Produces a self-contained document (no external styles or fonts) so a road
+//! can be dropped into a design review as is. Output is deterministic: the
+//! same path, grid and style always give byte-identical SVG, which is what
+//! the snapshot tests rely on.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::{validate_path, Point, DIRECTIONS};
+
+/// Appearance of the rendered SVG; missing fields take the defaults
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SvgStyle {
+    /// Side of one cell in pixels
+    pub cell_size: u32,
+    /// Width of the road line in pixels
+    pub road_width: u32,
+    /// Round the road's bends instead of drawing sharp corners
+    pub rounded_corners: bool,
+    /// Draw the cell grid
+    pub show_grid: bool,
+    /// Label every road cell with its path index
+    pub show_indices: bool,
+    pub background: String,
+    pub grid_color: String,
+    pub blocked_color: String,
+    pub wall_color: String,
+    pub road_color: String,
+    pub start_color: String,
+    pub goal_color: String,
+    pub label_color: String,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            cell_size: 32,
+            road_width: 10,
+            rounded_corners: false,
+            show_grid: true,
+            show_indices: false,
+            background: "#ffffff".to_string(),
+            grid_color: "#d0d0d0".to_string(),
+            blocked_color: "#404040".to_string(),
+            wall_color: "#1f6fb2".to_string(),
+            road_color: "#c8a165".to_string(),
+            start_color: "#2e9e44".to_string(),
+            goal_color: "#d0312d".to_string(),
+            label_color: "#202020".to_string(),
+        }
+    }
+}
+
+/// Escape a value for use inside a double-quoted attribute
+fn attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Render `path` on `grid` as an SVG document.
+///
+/// Draws the background, blocked cells, grid lines, walls, the road with its
+/// start and goal markers and, optionally, path index labels. `path` must be a
+/// simple path of adjacent open cells; it may be empty or partial.
+pub fn render_svg(path: &[Point], grid: &Grid, style: &SvgStyle) -> Result<String, SolveError> {
+    validate_path(path, grid)?;
+
+    let cell = style.cell_size.max(1) as f64;
+    let (width, height) = (grid.cols as f64 * cell, grid.rows as f64 * cell);
+    let center = |p: &Point| {
+        (
+            p.col as f64 * cell + cell / 2.0,
+            p.row as f64 * cell + cell / 2.0,
+        )
+    };
+
+    // Writing to a String cannot fail
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        svg,
+        r#"  <rect width="{}" height="{}" fill="{}"/>"#,
+        width,
+        height,
+        attr(&style.background)
+    );
+
+    if grid.has_obstacles() {
+        let _ = writeln!(
+            svg,
+            r#"  <g class="blocked" fill="{}">"#,
+            attr(&style.blocked_color)
+        );
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                if grid.is_blocked(row, col) {
+                    let _ = writeln!(
+                        svg,
+                        r#"    <rect x="{}" y="{}" width="{c}" height="{c}"/>"#,
+                        col as f64 * cell,
+                        row as f64 * cell,
+                        c = cell
+                    );
+                }
+            }
+        }
+        svg.push_str("  </g>\n");
+    }
+
+    if style.show_grid {
+        let mut d = String::new();
+        for col in 0..=grid.cols {
+            let _ = write!(d, "M{} 0V{}", col as f64 * cell, height);
+        }
+        for row in 0..=grid.rows {
+            let _ = write!(d, "M0 {}H{}", row as f64 * cell, width);
+        }
+        let _ = writeln!(
+            svg,
+            r#"  <path class="grid" d="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+            d,
+            attr(&style.grid_color)
+        );
+    }
+
+    if grid.has_walls() {
+        // Each wall is drawn once, from the cell above or to the left of it
+        let mut d = String::new();
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let (x, y) = (col as f64 * cell, row as f64 * cell);
+                for (direction, &(dr, dc, _)) in DIRECTIONS.iter().enumerate() {
+                    let outward = dr > 0 || dc > 0;
+                    let inside = row + dr < grid.rows && col + dc < grid.cols;
+                    if outward && inside && grid.is_walled(row, col, direction) {
+                        if dr > 0 {
+                            let _ = write!(d, "M{} {}H{}", x, y + cell, x + cell);
+                        } else {
+                            let _ = write!(d, "M{} {}V{}", x + cell, y, y + cell);
+                        }
+                    }
+                }
+            }
+        }
+        let _ = writeln!(
+            svg,
+            r#"  <path class="walls" d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+            d,
+            attr(&style.wall_color),
+            (cell / 8.0).max(2.0)
+        );
+    }
+
+    if let (Some(first), Some(last)) = (path.first(), path.last()) {
+        if path.len() > 1 {
+            let _ = writeln!(
+                svg,
+                r#"  <path class="road" d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="{}"/>"#,
+                road_path_data(path, &center, style.rounded_corners),
+                attr(&style.road_color),
+                style.road_width,
+                if style.rounded_corners {
+                    "round"
+                } else {
+                    "miter"
+                }
+            );
+        }
+
+        let radius = cell * 0.3;
+        for (class, point, color) in [
+            ("start", first, &style.start_color),
+            ("goal", last, &style.goal_color),
+        ] {
+            let (cx, cy) = center(point);
+            let _ = writeln!(
+                svg,
+                r#"  <circle class="{}" cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                class,
+                cx,
+                cy,
+                radius,
+                attr(color)
+            );
+        }
+    }
+
+    if style.show_indices && !path.is_empty() {
+        let _ = writeln!(
+            svg,
+            r#"  <g class="labels" fill="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+            attr(&style.label_color),
+            (cell * 0.35).round()
+        );
+        for (i, p) in path.iter().enumerate() {
+            let (x, y) = center(p);
+            let _ = writeln!(svg, r#"    <text x="{}" y="{}">{}</text>"#, x, y, i);
+        }
+        svg.push_str("  </g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// Path data through the cell centers, with points only where the road
+/// turns. Rounded bends run from the middle of one cell edge to the next
+/// with the corner cell's center as the curve's control point.
+fn road_path_data(path: &[Point], center: &dyn Fn(&Point) -> (f64, f64), rounded: bool) -> String {
+    let midpoint = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+
+    let (x, y) = center(&path[0]);
+    let mut d = format!("M{} {}", x, y);
+    for i in 1..path.len() - 1 {
+        let (prev, here, next) = (path[i - 1], path[i], path[i + 1]);
+        let turns = (here.row - prev.row, here.col - prev.col)
+            != (next.row - here.row, next.col - here.col);
+        if !turns {
+            continue;
+        }
+        let corner = center(&here);
+        if rounded {
+            let (ax, ay) = midpoint(center(&prev), corner);
+            let (bx, by) = midpoint(corner, center(&next));
+            let _ = write!(d, "L{} {}Q{} {} {} {}", ax, ay, corner.0, corner.1, bx, by);
+        } else {
+            let _ = write!(d, "L{} {}", corner.0, corner.1);
+        }
+    }
+    let (x, y) = center(&path[path.len() - 1]);
+    let _ = write!(d, "L{} {}", x, y);
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{p, snake};
+    use crate::Edge;
+    use std::path::PathBuf;
+
+    /// Compare against `src/snapshots/<name>`; set `UPDATE_SNAPSHOTS=1` to
+    /// rewrite the file after an intended change
+    fn assert_snapshot(name: &str, actual: &str) {
+        let file: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "snapshots", name]
+            .iter()
+            .collect();
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&file, actual).unwrap();
+        }
+        let expected = std::fs::read_to_string(&file)
+            .unwrap_or_else(|_| panic!("missing snapshot {}", file.display()));
+        assert!(
+            actual == expected,
+            "snapshot {} changed, rerun with UPDATE_SNAPSHOTS=1 to accept:\n{}",
+            name,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_snake_snapshot() {
+        let svg = render_svg(&snake(3, 3), &Grid::new(3, 3), &SvgStyle::default()).unwrap();
+        assert_snapshot("snake_3x3.svg", &svg);
+    }
+
+    #[test]
+    fn test_styled_snapshot() {
+        // A ring around a blocked center, fenced off from the top-left corner
+        let grid = Grid::new(3, 3)
+            .with_blocked(&[p(1, 1)])
+            .unwrap()
+            .with_walls(&[Edge {
+                from: p(0, 0),
+                to: p(1, 0),
+            }])
+            .unwrap();
+        let path = [
+            p(0, 0),
+            p(0, 1),
+            p(0, 2),
+            p(1, 2),
+            p(2, 2),
+            p(2, 1),
+            p(2, 0),
+            p(1, 0),
+        ];
+        let style = SvgStyle {
+            cell_size: 20,
+            rounded_corners: true,
+            show_indices: true,
+            show_grid: false,
+            ..SvgStyle::default()
+        };
+        let svg = render_svg(&path, &grid, &style).unwrap();
+        assert_snapshot("ring_rounded.svg", &svg);
+    }
+
+    #[test]
+    fn test_style_values_are_escaped_and_paths_checked() {
+        let style = SvgStyle {
+            road_color: r#"red" onload="x"#.to_string(),
+            ..SvgStyle::default()
+        };
+        let svg = render_svg(&[p(0, 0), p(0, 1)], &Grid::new(1, 2), &style).unwrap();
+        assert!(svg.contains(r#"stroke="red&quot; onload=&quot;x""#));
+
+        let partial: SvgStyle = serde_json::from_str(r#"{"cell_size":10}"#).unwrap();
+        assert_eq!(partial.road_width, SvgStyle::default().road_width);
+
+        assert!(render_svg(&[p(0, 0), p(1, 1)], &Grid::new(2, 2), &style).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+//! Helpers shared by the unit tests
+
+use crate::Point;
+
+pub(crate) fn p(row: i32, col: i32) -> Point {
+    Point { row, col }
+}
+
+/// Row-by-row boustrophedon from the top-left corner
+pub(crate) fn snake(rows: i32, cols: i32) -> Vec<Point> {
+    (0..rows)
+        .flat_map(|row| (0..cols).map(move |i| p(row, if row % 2 == 0 { i } else { cols - 1 - i })))
+        .collect()
+}
@@ -0,0 +1,151 @@
+//! Text rendering of a road with box-drawing characters
+//!
+//! Meant for debugging: `RoadGrid` prints as a picture in `Display`, `Debug`
+//! and therefore in `assert_eq!` failures, which is far easier to read than a
+//! list of points. It draws whatever it is given, including paths that skip
+//! cells or revisit them, so a broken path can still be inspected.
+
+use std::fmt;
+
+use crate::connections::Connections;
+use crate::grid::Grid;
+use crate::{direction_between, Point};
+
+/// Glyph for each connection mask (1 up, 2 down, 4 left, 8 right)
+const UNICODE: [char; 16] = [
+    '·', '╵', '╷', '│', '╴', '┘', '┐', '┤', '╶', '└', '┌', '├', '─', '┴', '┬', '┼',
+];
+
+/// The same glyphs in plain ASCII, for terminals without box drawing
+const ASCII: [char; 16] = [
+    '.', '|', '|', '|', '-', '+', '+', '+', '-', '+', '+', '+', '-', '+', '+', '+',
+];
+
+/// Road drawn on a grid, one character per cell.
+///
+/// The start is `S`, the end `E` and blocked cells `█`. Horizontal links
+/// are drawn between the columns so the road reads as a continuous line:
+///
+/// ```text
+/// S───┐
+/// ┌───┘
+/// └───E
+/// ```
+///
+/// The alternate form (`{:#}`) uses ASCII only (`#` for blocked cells).
+#[derive(Clone, PartialEq, Eq)]
+pub struct RoadGrid {
+    rows: i32,
+    cols: i32,
+    blocked: Vec<bool>,
+    connections: Vec<Connections>,
+    start: Option<Point>,
+    end: Option<Point>,
+}
+
+impl RoadGrid {
+    /// Draw `path` on `grid`. Cells outside the grid are ignored and steps
+    /// between non-adjacent cells are left undrawn.
+    pub fn new(path: &[Point], grid: &Grid) -> Self {
+        let cells = grid.rows.max(0) as usize * grid.cols.max(0) as usize;
+        let inside = |p: &Point| p.row >= 0 && p.row < grid.rows && p.col >= 0 && p.col < grid.cols;
+        let index = |p: &Point| (p.row * grid.cols + p.col) as usize;
+
+        let blocked = (0..cells)
+            .map(|i| grid.is_blocked(i as i32 / grid.cols, i as i32 % grid.cols))
+            .collect();
+        let mut connections = vec![Connections::NONE; cells];
+        for step in path.windows(2) {
+            if !(inside(&step[0]) && inside(&step[1])) {
+                continue;
+            }
+            if let Some(direction) = direction_between(step[0], step[1]) {
+                connections[index(&step[0])].insert(Connections::from_direction(direction));
+                connections[index(&step[1])].insert(Connections::from_direction(direction ^ 1));
+            }
+        }
+
+        RoadGrid {
+            rows: grid.rows,
+            cols: grid.cols,
+            blocked,
+            connections,
+            start: path.first().copied().filter(inside),
+            end: path.last().copied().filter(inside),
+        }
+    }
+
+    fn draw(&self, f: &mut fmt::Formatter<'_>, ascii: bool) -> fmt::Result {
+        let (glyphs, wall, link) = if ascii {
+            (&ASCII, '#', '-')
+        } else {
+            (&UNICODE, '█', '─')
+        };
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.cols {
+                let i = (row * self.cols + col) as usize;
+                let here = Some(Point { row, col });
+                let mask = self.connections[i];
+                let glyph = if self.blocked[i] {
+                    wall
+                } else if here == self.start {
+                    'S'
+                } else if here == self.end {
+                    'E'
+                } else {
+                    glyphs[mask.bits() as usize]
+                };
+                write!(f, "{}", glyph)?;
+                if col + 1 < self.cols {
+                    let linked = mask.contains(Connections::RIGHT);
+                    write!(f, "{}", if linked { link } else { ' ' })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RoadGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.draw(f, f.alternate())
+    }
+}
+
+impl fmt::Debug for RoadGrid {
+    /// Starts on a new line so the picture lines up in assertion messages
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        self.draw(f, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{p, snake};
+
+    #[test]
+    fn test_snake() {
+        let road = RoadGrid::new(&snake(3, 3), &Grid::new(3, 3));
+        assert_eq!(road.to_string(), "S───┐\n┌───┘\n└───E");
+        assert_eq!(format!("{:#}", road), "S---+\n+---+\n+---E");
+        assert_eq!(format!("{:?}", road), "\nS───┐\n┌───┘\n└───E");
+    }
+
+    #[test]
+    fn test_blocked_and_broken_paths() {
+        let grid = Grid::new(2, 3).with_blocked(&[p(0, 1)]).unwrap();
+        let road = RoadGrid::new(&[p(0, 0), p(1, 0), p(1, 1)], &grid);
+        assert_eq!(road.to_string(), "S █ ·\n└─E ·");
+        assert_eq!(format!("{:#}", road), "S # .\n+-E .");
+
+        // A jump leaves a dangling end; cells off the grid are skipped
+        let broken = [p(0, 0), p(1, 0), p(1, 1), p(1, 0), p(1, 2), p(5, 5)];
+        let road = RoadGrid::new(&broken, &Grid::new(2, 3));
+        assert_eq!(road.to_string(), "S · ·\n└─╴ ·");
+    }
+}
@@ -0,0 +1,195 @@
+//! Road tiles for tileset renderers
+//!
+//! Every road cell is one of a few sprites (end caps, straights and corners)
+//! placed with a rotation. The tile, its rotation and the sides the road
+//! enters and leaves by are derived here once, so renderers do not have to
+//! re-derive them from the connection names.
+
+use serde::{Deserialize, Serialize};
+
+use crate::connections::Connections;
+use crate::{direction_between, Point};
+
+/// One of the four grid directions, in `DIRECTIONS` order
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Direction of the step between two adjacent cells
+    fn between(from: Point, to: Point) -> Option<Direction> {
+        direction_between(from, to).map(|i| Self::ALL[i])
+    }
+
+    fn connection(self) -> Connections {
+        Connections::from_direction(self as usize)
+    }
+}
+
+/// Axis of a straight tile
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Corner tile, named by the two sides it joins (north is up)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+/// Sprite for a road cell
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RoadTile {
+    /// First cell of the road
+    StartCap,
+    /// Last cell of the road
+    EndCap,
+    Straight(Axis),
+    Corner(Corner),
+}
+
+/// Tile of one road cell with what a renderer needs to place it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TileCell {
+    pub tile: RoadTile,
+    /// Quarter turns clockwise from the base sprite (see
+    /// `Connections::rotation`)
+    pub rotation: u8,
+    /// Side the road comes in by; absent on the first cell
+    pub entry: Option<Direction>,
+    /// Side the road leaves by; absent on the last cell. Travel runs from
+    /// `entry` to `exit`.
+    pub exit: Option<Direction>,
+    pub path_index: usize,
+}
+
+/// Classify the cells of a path of adjacent cells, in path order
+pub(crate) fn tile_cells(path: &[Point]) -> Vec<TileCell> {
+    let last = path.len().saturating_sub(1);
+    path.iter()
+        .enumerate()
+        .map(|(i, &cell)| {
+            let entry = i
+                .checked_sub(1)
+                .and_then(|prev| Direction::between(cell, path[prev]));
+            let exit = path
+                .get(i + 1)
+                .and_then(|&next| Direction::between(cell, next));
+
+            let mut mask = Connections::NONE;
+            for direction in entry.into_iter().chain(exit) {
+                mask.insert(direction.connection());
+            }
+
+            let tile = if i == 0 {
+                RoadTile::StartCap
+            } else if i == last {
+                RoadTile::EndCap
+            } else if mask == Connections::LEFT | Connections::RIGHT {
+                RoadTile::Straight(Axis::Horizontal)
+            } else if mask.is_straight() {
+                RoadTile::Straight(Axis::Vertical)
+            } else {
+                let north = mask.contains(Connections::UP);
+                let east = mask.contains(Connections::RIGHT);
+                RoadTile::Corner(match (north, east) {
+                    (true, true) => Corner::NorthEast,
+                    (true, false) => Corner::NorthWest,
+                    (false, true) => Corner::SouthEast,
+                    (false, false) => Corner::SouthWest,
+                })
+            };
+
+            TileCell {
+                tile,
+                rotation: mask.rotation(),
+                entry,
+                exit,
+                path_index: i,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    #[test]
+    fn test_u_turn() {
+        // Down, right, then back up
+        let cells = tile_cells(&[p(0, 0), p(1, 0), p(1, 1), p(0, 1)]);
+        let tiles: Vec<RoadTile> = cells.iter().map(|c| c.tile).collect();
+        assert_eq!(
+            tiles,
+            [
+                RoadTile::StartCap,
+                RoadTile::Corner(Corner::NorthEast),
+                RoadTile::Corner(Corner::NorthWest),
+                RoadTile::EndCap,
+            ]
+        );
+        assert_eq!(
+            (cells[0].entry, cells[0].exit),
+            (None, Some(Direction::Down))
+        );
+        assert_eq!(cells[0].rotation, 2);
+        assert_eq!(
+            (cells[1].entry, cells[1].exit),
+            (Some(Direction::Up), Some(Direction::Right))
+        );
+        assert_eq!(cells[2].rotation, 3);
+        assert_eq!(
+            (cells[3].entry, cells[3].exit),
+            (Some(Direction::Down), None)
+        );
+        assert_eq!(cells[3].rotation, 2);
+    }
+
+    #[test]
+    fn test_straights_and_serde() {
+        let cells = tile_cells(&[p(0, 0), p(0, 1), p(0, 2), p(1, 2), p(2, 2)]);
+        assert_eq!(cells[1].tile, RoadTile::Straight(Axis::Horizontal));
+        assert_eq!(cells[1].rotation, 1);
+        assert_eq!(cells[2].tile, RoadTile::Corner(Corner::SouthWest));
+        assert_eq!(cells[3].tile, RoadTile::Straight(Axis::Vertical));
+        assert_eq!(cells[3].rotation, 0);
+
+        assert_eq!(
+            serde_json::to_string(&cells[2].tile).unwrap(),
+            r#"{"corner":"south_west"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&cells[0].tile).unwrap(),
+            r#""start_cap""#
+        );
+
+        let single = tile_cells(&[p(3, 3)]);
+        assert_eq!(single[0].tile, RoadTile::StartCap);
+        assert_eq!((single[0].entry, single[0].exit), (None, None));
+    }
+}
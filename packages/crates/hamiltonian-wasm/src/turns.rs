@@ -0,0 +1,207 @@
+//! Turn counting and turn-count optimization
+//!
+//! The heuristic search leaves the number of bends to chance. Given an
+//! objective, a found road is reshaped with the sampler's excursion moves,
+//! which keep both endpoints in place: a move is kept when it does not take
+//! the turn count further from the objective, so the road drifts towards it
+//! while sideways moves let it escape plateaus.
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::Grid;
+use crate::rng::Rng;
+use crate::sampler::Backbite;
+use crate::Point;
+
+/// Longest excursion the optimizer tries before giving a move up; each of
+/// its backbites reverses up to the whole road
+const MAX_EXCURSION: usize = 64;
+
+/// How many bends the road should have
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnObjective {
+    /// As straight as possible
+    Minimize,
+    /// As twisty as possible
+    Maximize,
+    /// Exactly this many turns
+    Target(u32),
+    /// This fraction of the cells that could turn (all but the endpoints)
+    Ratio(f64),
+}
+
+impl TurnObjective {
+    /// Distance of `turns` from the objective for a road of `cells` cells;
+    /// lower is better
+    fn score(&self, turns: u32, cells: usize) -> i64 {
+        let turns = turns as i64;
+        match *self {
+            TurnObjective::Minimize => turns,
+            TurnObjective::Maximize => -turns,
+            TurnObjective::Target(target) => (turns - target as i64).abs(),
+            TurnObjective::Ratio(ratio) => {
+                let target = (ratio.clamp(0.0, 1.0) * cells.saturating_sub(2) as f64).round();
+                (turns - target as i64).abs()
+            }
+        }
+    }
+}
+
+/// Number of cells where the road changes direction
+pub fn count_turns(path: &[Point]) -> u32 {
+    path.windows(3)
+        .filter(|w| is_turn(w[0], w[1], w[2]))
+        .count() as u32
+}
+
+/// Whether the road bends at `at` when passing from `from` to `to`
+#[inline]
+pub(crate) fn is_turn(from: Point, at: Point, to: Point) -> bool {
+    (at.row - from.row, at.col - from.col) != (to.row - at.row, to.col - at.col)
+}
+
+/// Reshape a Hamiltonian path of `grid` towards `objective` with `steps`
+/// excursions, alternating between the two ends so both halves of the road
+/// change. Moves that break `keep` (e.g. waypoint order or fixed edges) are
+/// rejected. The pass stops early once `out_of_time` returns true.
+pub(crate) fn optimize_turns(
+    path: Vec<Point>,
+    grid: &Grid,
+    objective: TurnObjective,
+    steps: u32,
+    rng: &mut Rng,
+    mut out_of_time: impl FnMut() -> bool,
+    keep: impl Fn(&Backbite) -> bool,
+) -> Vec<Point> {
+    if path.len() < 4 {
+        return path;
+    }
+
+    let cells = path.len();
+    let max_moves = cells.min(MAX_EXCURSION);
+    let mut chain = Backbite::new(grid, path);
+    let mut score = objective.score(chain.turns(), cells);
+    for step in 0..steps {
+        if out_of_time() {
+            break;
+        }
+        let moves = chain.excursion(step % 2 == 0, max_moves, rng);
+        if moves.is_empty() {
+            continue;
+        }
+        let candidate = objective.score(chain.turns(), cells);
+        if candidate <= score && keep(&chain) {
+            score = candidate;
+        } else {
+            chain.undo(&moves);
+        }
+    }
+    chain.into_path()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(row: i32, col: i32) -> Point {
+        Point { row, col }
+    }
+
+    /// Row-by-row boustrophedon, the straightest road on a rectangle
+    fn snake(rows: i32, cols: i32) -> Vec<Point> {
+        (0..rows)
+            .flat_map(|row| {
+                (0..cols).map(move |i| p(row, if row % 2 == 0 { i } else { cols - 1 - i }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_count_turns() {
+        assert_eq!(count_turns(&[]), 0);
+        assert_eq!(count_turns(&[p(0, 0), p(0, 1), p(0, 2)]), 0);
+        assert_eq!(count_turns(&[p(0, 0), p(0, 1), p(1, 1), p(1, 2)]), 2);
+        // Two bends per row change
+        assert_eq!(count_turns(&snake(4, 5)), 6);
+    }
+
+    #[test]
+    fn test_objectives_move_the_turn_count() {
+        let grid = Grid::new(6, 6);
+        let initial = snake(6, 6);
+        let before = count_turns(&initial);
+        let (first, last) = (initial[0], initial[35]);
+
+        let twisty = optimize_turns(
+            initial.clone(),
+            &grid,
+            TurnObjective::Maximize,
+            2_000,
+            &mut Rng::new(1),
+            || false,
+            |_| true,
+        );
+        assert!(count_turns(&twisty) > before);
+        assert_eq!((twisty[0], twisty[35]), (first, last));
+
+        let straight = optimize_turns(
+            twisty.clone(),
+            &grid,
+            TurnObjective::Minimize,
+            2_000,
+            &mut Rng::new(2),
+            || false,
+            |_| true,
+        );
+        assert!(count_turns(&straight) < count_turns(&twisty));
+
+        let target = optimize_turns(
+            initial,
+            &grid,
+            TurnObjective::Target(16),
+            2_000,
+            &mut Rng::new(3),
+            || false,
+            |_| true,
+        );
+        assert!(count_turns(&target).abs_diff(16) < 16 - before);
+    }
+
+    #[test]
+    fn test_rejected_moves_are_undone() {
+        let grid = Grid::new(5, 5);
+        let initial = snake(5, 5);
+        let path = optimize_turns(
+            initial.clone(),
+            &grid,
+            TurnObjective::Maximize,
+            500,
+            &mut Rng::new(4),
+            || false,
+            |_| false,
+        );
+        assert_eq!(path, initial);
+    }
+
+    #[test]
+    fn test_stops_when_out_of_time() {
+        let grid = Grid::new(5, 5);
+        let initial = snake(5, 5);
+        let mut checks = 0;
+        let path = optimize_turns(
+            initial.clone(),
+            &grid,
+            TurnObjective::Maximize,
+            500,
+            &mut Rng::new(4),
+            || {
+                checks += 1;
+                true
+            },
+            |_| true,
+        );
+        assert_eq!(path, initial);
+        assert_eq!(checks, 1);
+    }
+}
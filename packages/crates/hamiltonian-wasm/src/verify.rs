@@ -0,0 +1,283 @@
+//! Verification of submitted Hamiltonian paths
+//!
+//! Unlike `validate_path`, which stops at the first problem, the verifier
+//! reports every violation it finds with where it happens, so a server can
+//! log exactly how a submission was tampered with and a client can point at
+//! the offending cells.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::{direction_between, validate_grid, Edge, Point, MAX_GRID_CELLS};
+
+/// What a submitted path has to satisfy besides covering the grid
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PathConstraints {
+    /// Required first cell
+    pub start: Option<Point>,
+    /// Required last cell
+    pub end: Option<Point>,
+    /// Cells the path must visit in this order
+    pub waypoints: Vec<Point>,
+    /// Steps the path must take, in either direction
+    pub fixed_edges: Vec<Edge>,
+}
+
+/// Kind of a violation, serialized as its `kind` tag plus any details
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// The path does not have one cell per open cell of the grid
+    WrongLength { expected: usize, actual: usize },
+    /// The cell lies outside the grid
+    OutOfBounds,
+    /// The cell is blocked
+    BlockedCell,
+    /// The cell was already visited at path index `first`
+    Revisited { first: usize },
+    /// The step into the cell is not to a neighbour
+    NotAdjacent,
+    /// The step into the cell crosses a wall
+    CrossesWall,
+    /// The path does not start at `expected`
+    WrongStart { expected: Point },
+    /// The path does not end at `expected`
+    WrongEnd { expected: Point },
+    /// Waypoint `waypoint` is never visited
+    MissedWaypoint { waypoint: usize },
+    /// Waypoint `waypoint` is visited before an earlier one
+    WaypointOutOfOrder { waypoint: usize },
+    /// Fixed edge `edge` is not a step of the path
+    MissingFixedEdge { edge: usize },
+}
+
+/// One problem with a path
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Violation {
+    /// Path index of the offending cell; absent for problems with the path
+    /// as a whole, such as its length or a waypoint it never reaches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(flatten)]
+    pub kind: ViolationKind,
+}
+
+/// Check that `path` is a Hamiltonian path of `grid` meeting `constraints`.
+///
+/// Returns every violation found, ordered by kind of check and then by path
+/// index; an empty list means the path is valid. Only a malformed `grid` is
+/// an error.
+pub fn verify_hamiltonian_path(
+    path: &[Point],
+    grid: &Grid,
+    constraints: &PathConstraints,
+) -> Result<Vec<Violation>, SolveError> {
+    validate_grid(grid, MAX_GRID_CELLS)?;
+
+    let mut violations = Vec::new();
+    let mut report =
+        |index: Option<usize>, kind: ViolationKind| violations.push(Violation { index, kind });
+
+    let expected = grid.open_cells();
+    if path.len() != expected {
+        report(
+            None,
+            ViolationKind::WrongLength {
+                expected,
+                actual: path.len(),
+            },
+        );
+    }
+
+    let inside = |p: &Point| p.row >= 0 && p.row < grid.rows && p.col >= 0 && p.col < grid.cols;
+    // Path index of each cell's first visit
+    let mut visited: Vec<Option<usize>> = vec![None; grid.rows as usize * grid.cols as usize];
+    for (i, point) in path.iter().enumerate() {
+        if !inside(point) {
+            report(Some(i), ViolationKind::OutOfBounds);
+        } else {
+            if grid.is_blocked(point.row, point.col) {
+                report(Some(i), ViolationKind::BlockedCell);
+            }
+            let cell = (point.row * grid.cols + point.col) as usize;
+            match visited[cell] {
+                Some(first) => report(Some(i), ViolationKind::Revisited { first }),
+                None => visited[cell] = Some(i),
+            }
+        }
+
+        if i > 0 {
+            let prev = path[i - 1];
+            match direction_between(prev, *point) {
+                None => report(Some(i), ViolationKind::NotAdjacent),
+                Some(direction) => {
+                    if inside(&prev)
+                        && inside(point)
+                        && grid.is_walled(prev.row, prev.col, direction)
+                    {
+                        report(Some(i), ViolationKind::CrossesWall);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(expected) = constraints.start {
+        if path.first() != Some(&expected) {
+            report(
+                (!path.is_empty()).then_some(0),
+                ViolationKind::WrongStart { expected },
+            );
+        }
+    }
+    if let Some(expected) = constraints.end {
+        if path.last() != Some(&expected) {
+            report(
+                path.len().checked_sub(1),
+                ViolationKind::WrongEnd { expected },
+            );
+        }
+    }
+
+    let first_visit = |p: &Point| {
+        if inside(p) {
+            visited[(p.row * grid.cols + p.col) as usize]
+        } else {
+            None
+        }
+    };
+    let mut reached = None;
+    for (waypoint, point) in constraints.waypoints.iter().enumerate() {
+        match first_visit(point) {
+            None => report(None, ViolationKind::MissedWaypoint { waypoint }),
+            Some(at) if reached.is_some_and(|before| at < before) => {
+                report(Some(at), ViolationKind::WaypointOutOfOrder { waypoint })
+            }
+            Some(at) => reached = Some(at),
+        }
+    }
+
+    // On a path without revisits an edge is taken exactly when its cells sit
+    // next to each other; revisits are reported above
+    for (edge, fixed) in constraints.fixed_edges.iter().enumerate() {
+        let taken = match (first_visit(&fixed.from), first_visit(&fixed.to)) {
+            (Some(a), Some(b)) => a.abs_diff(b) == 1,
+            _ => false,
+        };
+        if !taken {
+            report(None, ViolationKind::MissingFixedEdge { edge });
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{p, snake};
+
+    fn kinds(violations: &[Violation]) -> Vec<(Option<usize>, ViolationKind)> {
+        violations.iter().map(|v| (v.index, v.kind)).collect()
+    }
+
+    #[test]
+    fn test_valid_path() {
+        let constraints = PathConstraints {
+            start: Some(p(0, 0)),
+            end: Some(p(2, 2)),
+            waypoints: vec![p(0, 2), p(1, 1)],
+            fixed_edges: vec![Edge {
+                from: p(2, 1),
+                to: p(2, 0),
+            }],
+        };
+        let violations = verify_hamiltonian_path(&snake(3, 3), &Grid::new(3, 3), &constraints);
+        assert_eq!(violations.unwrap(), []);
+    }
+
+    #[test]
+    fn test_tampered_path() {
+        use ViolationKind as V;
+        let grid = Grid::new(3, 3).with_blocked(&[p(2, 2)]).unwrap();
+        // Jumps to (2,0), revisits (1,0), steps off the grid and onto the
+        // blocked cell, and never reaches (1,1)
+        let path = [
+            p(0, 0),
+            p(0, 1),
+            p(0, 2),
+            p(1, 2),
+            p(2, 0),
+            p(1, 0),
+            p(1, 0),
+            p(1, -1),
+            p(2, 2),
+        ];
+        let constraints = PathConstraints {
+            start: Some(p(0, 0)),
+            end: Some(p(2, 1)),
+            waypoints: vec![p(1, 0), p(1, 1), p(0, 2)],
+            fixed_edges: vec![Edge {
+                from: p(0, 0),
+                to: p(1, 0),
+            }],
+        };
+        let violations = verify_hamiltonian_path(&path, &grid, &constraints).unwrap();
+        assert_eq!(
+            kinds(&violations),
+            [
+                (
+                    None,
+                    V::WrongLength {
+                        expected: 8,
+                        actual: 9
+                    }
+                ),
+                (Some(4), V::NotAdjacent),
+                (Some(6), V::Revisited { first: 5 }),
+                (Some(6), V::NotAdjacent),
+                (Some(7), V::OutOfBounds),
+                (Some(8), V::BlockedCell),
+                (Some(8), V::NotAdjacent),
+                (Some(8), V::WrongEnd { expected: p(2, 1) }),
+                (None, V::MissedWaypoint { waypoint: 1 }),
+                (Some(2), V::WaypointOutOfOrder { waypoint: 2 }),
+                (None, V::MissingFixedEdge { edge: 0 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walls_and_serde() {
+        let grid = Grid::new(1, 2)
+            .with_walls(&[Edge {
+                from: p(0, 0),
+                to: p(0, 1),
+            }])
+            .unwrap();
+        let violations =
+            verify_hamiltonian_path(&[p(0, 0), p(0, 1)], &grid, &PathConstraints::default())
+                .unwrap();
+        assert_eq!(kinds(&violations), [(Some(1), ViolationKind::CrossesWall)]);
+        assert_eq!(
+            serde_json::to_string(&violations).unwrap(),
+            r#"[{"index":1,"kind":"crosses_wall"}]"#
+        );
+
+        let empty = verify_hamiltonian_path(
+            &[],
+            &grid,
+            &PathConstraints {
+                start: Some(p(0, 0)),
+                ..PathConstraints::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&empty).unwrap(),
+            r#"[{"kind":"wrong_length","expected":2,"actual":0},{"kind":"wrong_start","expected":{"row":0,"col":0}}]"#
+        );
+    }
+}
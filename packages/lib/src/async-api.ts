@@ -27,15 +27,94 @@ export interface GridSize {
   cols: number
 }
 
+export interface Edge {
+  from: Point
+  to: Point
+}
+
+/** How many bends the road should have */
+export type TurnObjective =
+  | "minimize"
+  | "maximize"
+  | { target: number }
+  | { ratio: number }
+
+/**
+ * Settings of a path search; every field is optional
+ * Progress callbacks cannot cross the worker boundary, so there are none
+ */
+export interface SearchOptions {
+  maxIterations?: number
+  /** Non-zero entries are blocked cells */
+  blocked?: Uint8Array
+  /** Walled sides of each cell (1 up, 2 down, 4 left, 8 right) */
+  walls?: Uint8Array
+  seed?: number | bigint
+  timeLimitMs?: number
+  waypoints?: Point[]
+  fixedEdges?: Edge[]
+  turnObjective?: TurnObjective
+  turnSteps?: number
+}
+
+export interface SearchStats {
+  iterations: number
+  moves: number
+  connectivity_checks: number
+  connectivity_prunes: number
+  dead_end_prunes: number
+  max_depth: number
+  waypoint_prunes: number
+  waypoints_reached: number
+  pruned_waypoint?: number
+  fixed_edge_prunes: number
+}
+
+export type InfeasibleReason =
+  | "same_endpoints"
+  | "parity"
+  | "color_imbalance"
+  | "single_line"
+  | "ladder_rung"
+  | "three_wide_trap"
+  | "dead_end"
+  | "disconnected"
+  | "odd_cell_count"
+  | "exhausted"
+  | { unreachable_waypoint: { index: number } }
+
+export type Feasibility =
+  | { verdict: "possible" }
+  | { verdict: "impossible"; reason: InfeasibleReason }
+  | { verdict: "unknown" }
+
+export type Termination = "found" | "exhausted" | "iteration_limit" | "deadline" | "cancelled"
+
+export interface PartialPath {
+  path: Point[]
+  reaches_end: boolean
+  uncovered: Point[]
+}
+
 export interface PathResult {
   found: boolean
   path: Point[]
   iterations: number
+  verdict: Feasibility
+  termination?: Termination
+  stats?: SearchStats
+  partial?: PartialPath
+  turns?: number
 }
 
 export interface CellData {
+  /** Connected directions as a bitmask (1 up, 2 down, 4 left, 8 right) */
+  mask: number
+  /** Directions of the previous cell, then the next one */
   connections: string[]
   path_index: number
+  /** Connections that come from fixed edges */
+  fixed?: string[]
 }
 
 export type RoadGrid = (CellData | null)[][]
@@ -131,18 +210,19 @@ function sendMessage<T>(type: WorkerRequest["type"], payload: unknown): Promise<
 
 /**
  * Find a Hamiltonian path in a grid (async)
+ * A number for `options` is the iteration budget, as in earlier versions
  */
 export function findHamiltonianPathAsync(
   start: Point,
   end: Point,
   gridSize: GridSize,
-  maxIterations: number = 500000,
+  options: number | SearchOptions = 500000,
 ): Promise<PathResult> {
   return sendMessage<PathResult>("findPath", {
     start,
     end,
     gridSize,
-    maxIterations,
+    options,
   })
 }
 
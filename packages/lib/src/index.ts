@@ -6,6 +6,7 @@
 import init, {
   cell_parity,
   find_hamiltonian_path,
+  find_hamiltonian_path_with_options,
   has_different_parity,
   path_to_road_grid,
 } from "@hamiltonian/wasm/pkg/hamiltonian_wasm"
@@ -21,15 +22,112 @@ export interface GridSize {
   cols: number
 }
 
+export interface Edge {
+  from: Point
+  to: Point
+}
+
+/** How many bends the road should have */
+export type TurnObjective =
+  | "minimize"
+  | "maximize"
+  | { target: number }
+  | { ratio: number }
+
+/** Counters describing the work a search has done */
+export interface SearchStats {
+  iterations: number
+  /** Cells entered plus rejected moves; the unit of the time checks */
+  moves: number
+  connectivity_checks: number
+  connectivity_prunes: number
+  dead_end_prunes: number
+  max_depth: number
+  waypoint_prunes: number
+  waypoints_reached: number
+  /** Waypoint behind the latest waypoint prune */
+  pruned_waypoint?: number
+  fixed_edge_prunes: number
+}
+
+/**
+ * Settings of a path search; every field is optional
+ * Masks are row-major with one entry per cell
+ */
+export interface SearchOptions {
+  maxIterations?: number
+  /** Non-zero entries are blocked cells */
+  blocked?: Uint8Array
+  /** Walled sides of each cell (1 up, 2 down, 4 left, 8 right) */
+  walls?: Uint8Array
+  /** Reorders equally good moves; omit for the deterministic search */
+  seed?: number | bigint
+  timeLimitMs?: number
+  /** Cells the road must visit, in order */
+  waypoints?: Point[]
+  /** Segments the road must use */
+  fixedEdges?: Edge[]
+  turnObjective?: TurnObjective
+  turnSteps?: number
+  onProgress?: (stats: SearchStats) => void
+  progressInterval?: number
+}
+
+/** Why a Hamiltonian path between the endpoints cannot exist */
+export type InfeasibleReason =
+  | "same_endpoints"
+  | "parity"
+  | "color_imbalance"
+  | "single_line"
+  | "ladder_rung"
+  | "three_wide_trap"
+  | "dead_end"
+  | "disconnected"
+  | "odd_cell_count"
+  | "exhausted"
+  | { unreachable_waypoint: { index: number } }
+
+/** Whether a Hamiltonian path exists; "unknown" when the search gave up */
+export type Feasibility =
+  | { verdict: "possible" }
+  | { verdict: "impossible"; reason: InfeasibleReason }
+  | { verdict: "unknown" }
+
+/** Why a search stopped */
+export type Termination = "found" | "exhausted" | "iteration_limit" | "deadline" | "cancelled"
+
+/** Best-effort road when no Hamiltonian path was found */
+export interface PartialPath {
+  path: Point[]
+  /** Whether `path` ends at the requested end cell */
+  reaches_end: boolean
+  /** Open cells the path does not cover, in row-major order */
+  uncovered: Point[]
+}
+
 export interface PathResult {
   found: boolean
   path: Point[]
   iterations: number
+  verdict: Feasibility
+  /** How the search ended; absent when no search ran */
+  termination?: Termination
+  /** Work done by the search; absent when no search ran */
+  stats?: SearchStats
+  /** Only set when a search ran without finding a path */
+  partial?: PartialPath
+  /** Bends in the path; only set with a turn objective */
+  turns?: number
 }
 
 export interface CellData {
+  /** Connected directions as a bitmask (1 up, 2 down, 4 left, 8 right) */
+  mask: number
+  /** Directions of the previous cell, then the next one */
   connections: string[]
   path_index: number
+  /** Connections that come from fixed edges */
+  fixed?: string[]
 }
 
 export type RoadGrid = (CellData | null)[][]
@@ -69,23 +167,50 @@ function ensureWasmInitialized(): void {
 
 /**
  * Find a Hamiltonian path in a grid
+ * A number for `options` is the iteration budget, as in earlier versions
  */
 export function findHamiltonianPath(
   start: Point,
   end: Point,
   gridSize: GridSize,
-  maxIterations: number = 500000,
+  options: number | SearchOptions = 500000,
 ): PathResult {
   ensureWasmInitialized()
 
-  const result = find_hamiltonian_path(
+  if (typeof options === "number") {
+    const result = find_hamiltonian_path(
+      start.row,
+      start.col,
+      end.row,
+      end.col,
+      gridSize.rows,
+      gridSize.cols,
+      options,
+    )
+
+    return result as PathResult
+  }
+
+  const result = find_hamiltonian_path_with_options(
     start.row,
     start.col,
     end.row,
     end.col,
     gridSize.rows,
     gridSize.cols,
-    maxIterations,
+    {
+      max_iterations: options.maxIterations,
+      blocked: options.blocked,
+      walls: options.walls,
+      seed: options.seed,
+      time_limit_ms: options.timeLimitMs,
+      waypoints: options.waypoints,
+      fixed_edges: options.fixedEdges,
+      turn_objective: options.turnObjective,
+      turn_steps: options.turnSteps,
+      on_progress: options.onProgress,
+      progress_interval: options.progressInterval,
+    },
   )
 
   return result as PathResult
@@ -130,7 +255,12 @@ export function createEmptyGrid(gridSize: GridSize): RoadGrid {
 }
 
 // Re-export types
-export type { PathResult as PathResultType, CellData as CellDataType }
+export type {
+  Feasibility as FeasibilityType,
+  PathResult as PathResultType,
+  CellData as CellDataType,
+  SearchOptions as SearchOptionsType,
+}
 
 // Re-export async API
 export {